@@ -0,0 +1,154 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use rand::{seq::SliceRandom, thread_rng};
+use slagskip::core::{Fire, Game, Grid, Hull, Player, Point, Strategy};
+
+/// A fleet placed at random, and every point of the grid in a random order to fire at.
+fn setup() -> (Vec<Hull>, Vec<Point>) {
+    let mut player = Player::new("Alice", 10);
+    player.place_ships_randomly().unwrap();
+    let mut points = player.grid.points().collect::<Vec<_>>();
+    points.shuffle(&mut thread_rng());
+    (player.grid.hulls().to_vec(), points)
+}
+
+/// Fires until the whole fleet is sunk, and returns the number of shots.
+fn play_bitboard((hulls, points): (Vec<Hull>, Vec<Point>)) -> usize {
+    let mut grid = Grid::new(10);
+    for hull in &hulls {
+        grid.place_ship(hull.ship, hull.origin, hull.direction);
+    }
+    let mut afloat = hulls.len();
+    let mut shots = 0;
+    for point in points {
+        shots += 1;
+        if let Some(Fire::Sunk(_)) = grid.fire_at(point) {
+            afloat -= 1;
+            if afloat == 0 {
+                break;
+            }
+        }
+    }
+    shots
+}
+
+fn play_nested((hulls, points): (Vec<Hull>, Vec<Point>)) -> usize {
+    let grid = nested::Grid::new(10);
+    for hull in &hulls {
+        grid.place_ship(*hull);
+    }
+    let mut afloat = hulls.len();
+    let mut shots = 0;
+    for point in points {
+        shots += 1;
+        if let Some(Fire::Sunk(_)) = grid.fire_at(point) {
+            afloat -= 1;
+            if afloat == 0 {
+                break;
+            }
+        }
+    }
+    shots
+}
+
+fn grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("grid");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("bitboard", |b| {
+        b.iter_batched(setup, play_bitboard, BatchSize::SmallInput)
+    });
+    group.bench_function("nested", |b| {
+        b.iter_batched(setup, play_nested, BatchSize::SmallInput)
+    });
+    group.finish();
+}
+
+fn game(c: &mut Criterion) {
+    let mut group = c.benchmark_group("game");
+    group.throughput(Throughput::Elements(1));
+    for strategy in [Strategy::Random, Strategy::HuntTarget] {
+        group.bench_function(strategy.to_string(), |b| {
+            b.iter(|| {
+                let mut game = Game::new(10);
+                game.add_player("Alice").place_ships_randomly().unwrap();
+                game.add_player("Bob").place_ships_randomly().unwrap();
+                let mut game = game.start().unwrap();
+                while game.result().is_none() {
+                    game.fire_with(strategy, 0, 1);
+                    game.fire_with(strategy, 1, 0);
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// The grid as it was before it became a bitboard, with a `Vec` of cells per column,
+/// for comparison.
+mod nested {
+    use std::cell::RefCell;
+
+    use slagskip::core::{Fire, Hull, Point, Ship};
+
+    #[derive(Clone)]
+    struct Cell {
+        ship: RefCell<Option<Ship>>,
+        hit: RefCell<bool>,
+    }
+
+    pub struct Grid {
+        size: usize,
+        cells: Vec<Vec<Cell>>,
+        hulls: RefCell<Vec<Hull>>,
+    }
+
+    impl Grid {
+        pub fn new(size: usize) -> Self {
+            let cell = Cell {
+                ship: RefCell::new(None),
+                hit: RefCell::new(false),
+            };
+            Grid {
+                size,
+                cells: vec![vec![cell; size]; size],
+                hulls: RefCell::new(vec![]),
+            }
+        }
+
+        fn at(&self, point: Point) -> Option<&Cell> {
+            if (point.0 >= self.size) || (point.1 >= self.size) {
+                return None;
+            }
+            Some(&self.cells[point.0][point.1])
+        }
+
+        pub fn place_ship(&self, hull: Hull) {
+            for point in hull.points() {
+                if let Some(cell) = self.at(point) {
+                    *cell.ship.borrow_mut() = Some(hull.ship);
+                }
+            }
+            self.hulls.borrow_mut().push(hull);
+        }
+
+        pub fn fire_at(&self, point: Point) -> Option<Fire> {
+            let cell = self.at(point)?;
+            if *cell.hit.borrow() {
+                return None;
+            }
+            *cell.hit.borrow_mut() = true;
+            if cell.ship.borrow().to_owned().is_none() {
+                return Some(Fire::Miss);
+            }
+            let mut hulls = self.hulls.borrow_mut();
+            let hull = hulls.iter_mut().find(|hull| hull.contains(point))?;
+            hull.hits += 1;
+            match hull.is_sunk() {
+                true => Some(Fire::Sunk(hull.ship)),
+                false => Some(Fire::Hit),
+            }
+        }
+    }
+}
+
+criterion_group!(benches, grid, game);
+criterion_main!(benches);
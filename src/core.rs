@@ -1,7 +1,11 @@
+mod clock;
 mod game;
 mod grid;
 mod player;
+mod rules;
 mod ship;
+mod stats;
+mod strategy;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct New;
@@ -9,7 +13,11 @@ pub struct New;
 #[derive(Debug, Eq, PartialEq)]
 pub struct Active;
 
-pub use crate::core::game::{Game, GameResult};
+pub use crate::core::clock::{OnTimeout, ShotClock, Timeout};
+pub use crate::core::game::{DrawReason, Game, GameResult, Shot};
 pub use crate::core::grid::{Cell, Fire, Grid, Point};
 pub use crate::core::player::Player;
-pub use crate::core::ship::{Direction, Ship};
+pub use crate::core::rules::{Resolution, Rules, Variant};
+pub use crate::core::ship::{Direction, Hull, Ship};
+pub use crate::core::stats::Stats;
+pub use crate::core::strategy::{Difficulty, Strategy};
@@ -0,0 +1,51 @@
+use std::{fmt, time::Duration};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ShotClock {
+    pub limit: Duration,
+    pub on_timeout: OnTimeout,
+}
+
+impl fmt::Display for ShotClock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}s per turn, then {}",
+            self.limit.as_secs(),
+            self.on_timeout
+        )
+    }
+}
+
+/// What happens when a player runs out of time.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum OnTimeout {
+    /// A shot is fired at a random cell on the player's behalf.
+    #[default]
+    RandomShot,
+
+    /// The player loses the turn.
+    SkipTurn,
+
+    /// The player forfeits the game.
+    Forfeit,
+}
+
+/// The outcome of a turn that ran out of time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Timeout {
+    Fired(super::Shot),
+    Skipped,
+    Forfeited,
+}
@@ -1,14 +1,24 @@
+use std::time::{Duration, Instant};
+
 use eyre::{eyre, Result};
 
 use crate::core::player::Player;
 
-use super::{Active, New};
+use super::{Active, Fire, Grid, New, OnTimeout, Point, Resolution, Rules, Strategy, Timeout};
 
 #[derive(Debug)]
 pub struct Game<Stage> {
     stage: std::marker::PhantomData<Stage>,
     pub grid_size: usize,
+    pub rules: Rules,
     pub players: Vec<Player<Stage>>,
+    turn_started: Instant,
+
+    /// Set if the game was called off as a draw while players were still alive.
+    draw: Option<DrawReason>,
+
+    /// The players who have yet to take their turn in the current round.
+    to_fire: Vec<bool>,
 }
 
 impl Default for Game<Active> {
@@ -17,7 +27,11 @@ impl Default for Game<Active> {
         Self {
             stage: std::marker::PhantomData,
             grid_size: 10,
+            rules: Rules::for_grid(10),
             players: vec![Player::<Active>::default(), Player::<Active>::default()],
+            turn_started: Instant::now(),
+            draw: None,
+            to_fire: vec![false; 2],
         }
     }
 }
@@ -27,14 +41,35 @@ impl Game<New> {
         Self {
             stage: std::marker::PhantomData,
             grid_size,
+            rules: Rules::for_grid(grid_size),
             players: vec![],
+            turn_started: Instant::now(),
+            draw: None,
+            to_fire: vec![],
         }
     }
 
-    pub fn add_player(&mut self, name: &str) -> &Player<New> {
-        let player = Player::new(name, self.grid_size);
+    pub fn with_rules(grid_size: usize, rules: Rules) -> Result<Game<New>> {
+        rules.validate(grid_size)?;
+        Ok(Self {
+            stage: std::marker::PhantomData,
+            grid_size,
+            rules,
+            players: vec![],
+            turn_started: Instant::now(),
+            draw: None,
+            to_fire: vec![],
+        })
+    }
+
+    pub fn add_player(&mut self, name: &str) -> &mut Player<New> {
+        let player = Player::with_rules(name, self.grid_size, &self.rules);
         self.players.push(player);
-        self.players.last().unwrap()
+        self.players.last_mut().unwrap()
+    }
+
+    pub fn remove_player(&mut self, name: &str) {
+        self.players.retain(|np| np.name != name);
     }
 
     pub fn is_ready(&self) -> bool {
@@ -50,12 +85,16 @@ impl Game<New> {
             .players
             .into_iter()
             .filter_map(|np| np.ready().ok())
-            .collect();
+            .collect::<Vec<_>>();
 
         Ok(Game {
             stage: std::marker::PhantomData,
             grid_size: self.grid_size,
+            rules: self.rules,
+            to_fire: vec![true; players.len()],
             players,
+            turn_started: Instant::now(),
+            draw: None,
         })
     }
 }
@@ -65,52 +104,227 @@ impl Game<Active> {
         self.players.iter().filter(|p| p.is_alive()).collect()
     }
 
-    pub fn result(&self) -> Option<GameResult> {
+    /// Returns the result, once the round is over for any players eliminated in it.
+    pub fn result(&self) -> Option<GameResult<'_>> {
+        if (0..self.players.len()).any(|player| self.has_turn_left(player)) {
+            return None;
+        }
         match self.alive_players()[..] {
             [player] => Some(GameResult::Winner(player)),
-            [] => Some(GameResult::Draw),
-            _ => None,
+            [] => Some(GameResult::Draw(DrawReason::AllEliminated)),
+            _ => self.draw.map(GameResult::Draw),
+        }
+    }
+
+    /// Ends the game as a draw, even though more than one player is still alive.
+    pub fn end_in_draw(&mut self, reason: DrawReason) {
+        if self.result().is_none() {
+            self.draw = Some(reason);
+        }
+    }
+
+    /// Whether the player was eliminated during the round, but may still take their turn
+    /// in it, which only happens with simultaneous resolution.
+    fn has_turn_left(&self, player: usize) -> bool {
+        self.rules.resolution == Resolution::Simultaneous
+            && self.to_fire.get(player) == Some(&true)
+            && !self.players[player].is_alive()
+    }
+
+    /// Ends the player's turn in the current round. Once every player has had their
+    /// turn, a new round starts for the players still alive.
+    ///
+    /// Callers that take turns one at a time must call this after each turn, while
+    /// `play_round` does so by itself.
+    pub fn end_turn(&mut self, player: usize) {
+        if let Some(to_fire) = self.to_fire.get_mut(player) {
+            *to_fire = false;
+        }
+        if !self.to_fire.contains(&true) {
+            self.to_fire = self.players.iter().map(|p| p.is_alive()).collect();
+        }
+    }
+
+    /// Gives up the game for the player, who also loses any turn left in the round.
+    pub fn forfeit(&mut self, player: usize) {
+        self.players[player].forfeit();
+        self.end_turn(player);
+    }
+
+    /// Fires a shot by one player at another, unless the point is off the grid or has
+    /// been fired at before.
+    ///
+    /// An eliminated shooter cannot fire, unless resolution is simultaneous and they
+    /// still have their turn in the round they were eliminated.
+    pub fn fire(&mut self, shooter: usize, target: usize, point: Point) -> Option<Shot> {
+        if !self.players.get(shooter)?.is_alive() && !self.has_turn_left(shooter) {
+            return None;
+        }
+        let fire = self.players.get_mut(target)?.fire_at(point)?;
+        Some(Shot {
+            shooter,
+            target,
+            point,
+            fire,
+        })
+    }
+
+    /// Fires a shot where the strategy picks on the target's grid.
+    pub fn fire_with(&mut self, strategy: Strategy, shooter: usize, target: usize) -> Option<Shot> {
+        let point = strategy.pick(self.players.get(target)?)?;
+        self.fire(shooter, target, point)
+    }
+
+    /// Restarts the shot clock, which must be done whenever a new turn starts.
+    pub fn start_turn(&mut self, now: Instant) {
+        self.turn_started = now;
+    }
+
+    /// Returns the time left of the current turn, if the rules have a shot clock.
+    pub fn time_left(&self, now: Instant) -> Option<Duration> {
+        self.rules.shot_clock.map(|shot_clock| {
+            shot_clock
+                .limit
+                .saturating_sub(now.saturating_duration_since(self.turn_started))
+        })
+    }
+
+    /// Acts on behalf of the shooter if the current turn has run out of time.
+    ///
+    /// A random shot is fired like any other shot, so it is skipped if the shooter may
+    /// not fire. If there was a timeout, the caller must move on to the next turn.
+    pub fn enforce_shot_clock(
+        &mut self,
+        now: Instant,
+        shooter: usize,
+        target: usize,
+    ) -> Option<Timeout> {
+        let shot_clock = self.rules.shot_clock?;
+        if !self.time_left(now)?.is_zero() || self.result().is_some() {
+            return None;
+        }
+
+        self.start_turn(now);
+        match shot_clock.on_timeout {
+            OnTimeout::RandomShot => match self.fire_with(Strategy::Random, shooter, target) {
+                Some(shot) => Some(Timeout::Fired(shot)),
+                None => Some(Timeout::Skipped),
+            },
+            OnTimeout::SkipTurn => Some(Timeout::Skipped),
+            OnTimeout::Forfeit => {
+                self.forfeit(shooter);
+                Some(Timeout::Forfeited)
+            }
         }
     }
 
-    pub fn round(&self) -> Vec<Turn> {
+    /// Returns the players' grids as they were after only the given shots.
+    pub fn grids_after(&self, shots: &[Shot]) -> Vec<Grid> {
         self.players
             .iter()
-            .map(|player| {
-                Turn::new(
-                    player,
-                    self.alive_players()
-                        .into_iter()
-                        .filter(|p| *p != player)
-                        .collect(),
+            .enumerate()
+            .map(|(i, player)| {
+                player.grid.with_hits(
+                    shots
+                        .iter()
+                        .filter(|shot| shot.target == i)
+                        .map(|shot| shot.point),
                 )
             })
             .collect()
     }
-}
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct Turn<'a> {
-    pub player: &'a Player<Active>,
-    pub opponents: Vec<&'a Player<Active>>,
-}
+    /// The indices of the players still alive, other than the given player.
+    pub fn opponents(&self, player: usize) -> Vec<usize> {
+        (0..self.players.len())
+            .filter(|i| *i != player && self.players[*i].is_alive())
+            .collect()
+    }
 
-impl<'a> Turn<'a> {
-    fn new(player: &'a Player<Active>, opponents: Vec<&'a Player<Active>>) -> Self {
-        Self { player, opponents }
+    /// Plays a round where every player fires once at each opponent, at the point that
+    /// `pick` chooses on the opponent's grid, and returns the shots fired.
+    ///
+    /// With sequential resolution, the players fire one after the other, and a player
+    /// eliminated earlier in the round loses their turn. With simultaneous resolution,
+    /// all players alive at the start of the round aim at the grids as they were then,
+    /// and the shots are resolved together, so the last players may all be eliminated.
+    /// A point aimed at by several players is only hit once.
+    pub fn play_round(
+        &mut self,
+        mut pick: impl FnMut(usize, &Player<Active>) -> Option<Point>,
+    ) -> Vec<Shot> {
+        let shots = match self.rules.resolution {
+            Resolution::Sequential => {
+                let mut shots = vec![];
+                for shooter in 0..self.players.len() {
+                    for target in self.opponents(shooter) {
+                        let point = pick(shooter, &self.players[target]);
+                        shots.extend(point.and_then(|point| self.fire(shooter, target, point)));
+                    }
+                }
+                shots
+            }
+            Resolution::Simultaneous => {
+                let aims = (0..self.players.len())
+                    .filter(|shooter| self.players[*shooter].is_alive())
+                    .flat_map(|shooter| {
+                        self.opponents(shooter)
+                            .into_iter()
+                            .map(move |target| (shooter, target))
+                    })
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .filter_map(|(shooter, target)| {
+                        Some((shooter, target, pick(shooter, &self.players[target])?))
+                    })
+                    .collect::<Vec<_>>();
+                aims.into_iter()
+                    .filter_map(|(shooter, target, point)| self.fire(shooter, target, point))
+                    .collect()
+            }
+        };
+        for player in 0..self.players.len() {
+            self.end_turn(player);
+        }
+        shots
     }
 }
 
+/// A shot fired by one player at another, identified by their index in `Game::players`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Shot {
+    pub shooter: usize,
+    pub target: usize,
+    pub point: Point,
+    pub fire: Fire,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum GameResult<'a> {
     Winner(&'a Player<Active>),
-    Draw,
+    Draw(DrawReason),
+}
+
+/// Why a game ended without a winner.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, strum_macros::EnumIter, strum_macros::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DrawReason {
+    /// The last players were all sunk in the same round.
+    AllEliminated,
+
+    /// No one could fire at anything, though ships were still afloat.
+    Stalemate,
+
+    /// The game went on for as many rounds as allowed.
+    RoundLimit,
 }
 
 #[cfg(test)]
 mod tests {
     use eyre::Result;
 
+    use crate::core::{Direction, Ship, ShotClock, Variant};
+
     use super::*;
 
     #[test]
@@ -135,7 +349,320 @@ mod tests {
     }
 
     #[test]
-    fn round_pairs_each_player_with_opponents() -> Result<()> {
+    fn game_setup_with_rules() -> Result<()> {
+        let rules = Rules {
+            fleet: vec![Ship::Cruiser, Ship::Destroyer],
+            variant: Variant::NoTouch,
+            shot_clock: None,
+            resolution: Resolution::Sequential,
+        };
+        let mut new_game = Game::with_rules(5, rules)?;
+
+        let alice = new_game.add_player("Alice");
+        assert_eq!(alice.to_place, vec![Ship::Cruiser, Ship::Destroyer]);
+        alice.place_ships_randomly()?;
+        assert_eq!(
+            alice
+                .grid
+                .cells()
+                .filter(|c| c.has_ship().is_some())
+                .count(),
+            5
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn game_with_invalid_rules() {
+        let rules = Rules {
+            fleet: vec![Ship::Carrier],
+            variant: Variant::Classic,
+            shot_clock: None,
+            resolution: Resolution::Sequential,
+        };
+
+        assert!(Game::with_rules(3, rules).is_err());
+    }
+
+    #[test]
+    fn grids_after_shots() -> Result<()> {
+        let mut new_game = Game::new(2);
+        new_game.add_player("Alice").place_ship(
+            Ship::Destroyer,
+            Point(0, 0),
+            Direction::Horizontal,
+        )?;
+        new_game.add_player("Bob").place_ship(
+            Ship::Destroyer,
+            Point(0, 1),
+            Direction::Horizontal,
+        )?;
+        let mut game = new_game.start()?;
+        let log = [
+            game.fire(0, 1, Point(0, 0)).unwrap(),
+            game.fire(1, 0, Point(0, 0)).unwrap(),
+        ];
+
+        let grids = game.grids_after(&log[..1]);
+
+        assert_eq!(grids[0].to_string(), ["OO", ".."].join("\n"));
+        assert_eq!(grids[1].to_string(), ["_.", "OO"].join("\n"));
+        assert_eq!(game.players[0].grid.to_string(), ["XO", ".."].join("\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn fire_through_game() -> Result<()> {
+        let mut game = game_one_hit_from_the_end(&["Alice", "Bob"], Resolution::Sequential)?;
+
+        let shot = game.fire(0, 1, Point(0, 1)).unwrap();
+
+        assert_eq!((shot.shooter, shot.target), (0, 1));
+        assert_eq!((shot.point, shot.fire), (Point(0, 1), Fire::Miss));
+        assert_eq!(game.players[1].shots_received(), 2);
+        assert_eq!(game.fire(0, 1, Point(0, 1)), None);
+        assert_eq!(game.fire(0, 1, Point(2, 0)), None);
+        assert_eq!(game.fire(0, 2, Point(0, 0)), None);
+
+        let shot = game.fire_with(Strategy::Random, 1, 0).unwrap();
+        assert!(game.players[0].grid.at(shot.point).unwrap().is_hit());
+        Ok(())
+    }
+
+    #[test]
+    fn game_can_be_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Game<New>>();
+        assert_send_sync::<Game<Active>>();
+    }
+
+    fn game_with_shot_clock(on_timeout: OnTimeout) -> Result<Game<Active>> {
+        let rules = Rules {
+            shot_clock: Some(ShotClock {
+                limit: Duration::from_secs(10),
+                on_timeout,
+            }),
+            ..Rules::for_grid(10)
+        };
+        let mut new_game = Game::with_rules(10, rules)?;
+        new_game.add_player("Alice").place_ships_randomly()?;
+        new_game.add_player("Bob").place_ships_randomly()?;
+        new_game.start()
+    }
+
+    #[test]
+    fn shot_clock_counts_down() -> Result<()> {
+        let mut game = game_with_shot_clock(OnTimeout::SkipTurn)?;
+        let now = Instant::now();
+        game.start_turn(now);
+
+        assert_eq!(game.time_left(now), Some(Duration::from_secs(10)));
+        assert_eq!(
+            game.time_left(now + Duration::from_secs(4)),
+            Some(Duration::from_secs(6))
+        );
+        assert_eq!(
+            game.time_left(now + Duration::from_secs(20)),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(game.enforce_shot_clock(now, 0, 1), None);
+        assert_eq!(Game::<Active>::default().time_left(now), None);
+        Ok(())
+    }
+
+    #[test]
+    fn shot_clock_fires_random_shot() -> Result<()> {
+        let mut game = game_with_shot_clock(OnTimeout::RandomShot)?;
+        let now = Instant::now();
+        game.start_turn(now);
+        let later = now + Duration::from_secs(10);
+
+        let timeout = game.enforce_shot_clock(later, 0, 1);
+
+        let Some(Timeout::Fired(shot)) = timeout else {
+            panic!("Expected a random shot, got {:?}", timeout);
+        };
+        assert_eq!((shot.shooter, shot.target), (0, 1));
+        assert!(game.players[1].grid.at(shot.point).unwrap().is_hit());
+        // The clock restarts for the next turn
+        assert_eq!(game.time_left(later), Some(Duration::from_secs(10)));
+        Ok(())
+    }
+
+    #[test]
+    fn shot_clock_forfeits() -> Result<()> {
+        let mut game = game_with_shot_clock(OnTimeout::Forfeit)?;
+        let now = Instant::now();
+        game.start_turn(now);
+
+        let timeout = game.enforce_shot_clock(now + Duration::from_secs(10), 0, 1);
+
+        assert_eq!(timeout, Some(Timeout::Forfeited));
+        assert!(matches!(game.result(), Some(GameResult::Winner(p)) if p.name == "Bob"));
+        Ok(())
+    }
+
+    #[test]
+    fn shot_clock_skips_eliminated_shooter() -> Result<()> {
+        let rules = Rules {
+            fleet: vec![Ship::Destroyer],
+            shot_clock: Some(ShotClock {
+                limit: Duration::from_secs(10),
+                on_timeout: OnTimeout::RandomShot,
+            }),
+            ..Rules::for_grid(2)
+        };
+        let mut new_game = Game::with_rules(2, rules)?;
+        for name in ["Alice", "Bob", "Cecil"] {
+            new_game.add_player(name).place_ship(
+                Ship::Destroyer,
+                Point(0, 0),
+                Direction::Horizontal,
+            )?;
+        }
+        let mut game = new_game.start()?;
+        game.fire(0, 1, Point(0, 0));
+        game.fire(0, 1, Point(1, 0));
+        let now = Instant::now();
+        game.start_turn(now);
+
+        // Bob is out, so the turn runs out without a shot
+        let timeout = game.enforce_shot_clock(now + Duration::from_secs(10), 1, 2);
+
+        assert_eq!(timeout, Some(Timeout::Skipped));
+        assert_eq!(game.players[2].shots_received(), 0);
+        Ok(())
+    }
+
+    /// A game where each player has a destroyer, and has taken a hit on it.
+    fn game_one_hit_from_the_end(players: &[&str], resolution: Resolution) -> Result<Game<Active>> {
+        let rules = Rules {
+            fleet: vec![Ship::Destroyer],
+            resolution,
+            ..Rules::for_grid(2)
+        };
+        let mut new_game = Game::with_rules(2, rules)?;
+        for name in players {
+            new_game.add_player(name).place_ship(
+                Ship::Destroyer,
+                Point(0, 0),
+                Direction::Horizontal,
+            )?;
+        }
+        let mut game = new_game.start()?;
+        for target in 0..players.len() {
+            game.fire((target + 1) % players.len(), target, Point(0, 0));
+        }
+        Ok(game)
+    }
+
+    /// Aims at the last part of the destroyer for the given pairs, and misses otherwise.
+    fn sink_only<'a>(
+        pairs: &'a [(usize, &'a str)],
+    ) -> impl FnMut(usize, &Player<Active>) -> Option<Point> + 'a {
+        |shooter, target| match pairs.contains(&(shooter, target.name.as_str())) {
+            true => Some(Point(1, 0)),
+            false => Some(Point(0, 1)),
+        }
+    }
+
+    #[test]
+    fn sequential_elimination_loses_remaining_turns() -> Result<()> {
+        let mut game = game_one_hit_from_the_end(&["Alice", "Bob"], Resolution::Sequential)?;
+
+        let shots = game.play_round(|_, _| Some(Point(1, 0)));
+
+        assert_eq!(shots.len(), 1);
+        assert!(matches!(game.result(), Some(GameResult::Winner(p)) if p.name == "Alice"));
+        assert_eq!(game.fire(1, 0, Point(1, 0)), None);
+        Ok(())
+    }
+
+    #[test]
+    fn simultaneous_elimination_is_a_draw() -> Result<()> {
+        let mut game = game_one_hit_from_the_end(&["Alice", "Bob"], Resolution::Simultaneous)?;
+
+        let shots = game.play_round(|_, _| Some(Point(1, 0)));
+
+        assert_eq!(shots.len(), 2);
+        assert!(matches!(
+            game.result(),
+            Some(GameResult::Draw(DrawReason::AllEliminated))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn simultaneous_turns_one_at_a_time() -> Result<()> {
+        let mut game = game_one_hit_from_the_end(&["Alice", "Bob"], Resolution::Simultaneous)?;
+
+        // Bob is sunk, but still has a turn in the round
+        game.fire(0, 1, Point(1, 0));
+        game.end_turn(0);
+        assert!(game.result().is_none());
+        assert_eq!(
+            game.fire(1, 0, Point(0, 1)).map(|s| s.fire),
+            Some(Fire::Miss)
+        );
+        game.end_turn(1);
+
+        assert!(matches!(game.result(), Some(GameResult::Winner(p)) if p.name == "Alice"));
+        assert_eq!(game.fire(1, 0, Point(1, 0)), None);
+
+        // Giving up ends the turn left, too
+        let mut game = game_one_hit_from_the_end(&["Alice", "Bob"], Resolution::Simultaneous)?;
+        game.fire(0, 1, Point(1, 0));
+        game.end_turn(0);
+        game.forfeit(1);
+        assert!(matches!(game.result(), Some(GameResult::Winner(p)) if p.name == "Alice"));
+        Ok(())
+    }
+
+    #[test]
+    fn simultaneous_elimination_of_some_players() -> Result<()> {
+        let players = ["Alice", "Bob", "Cecil"];
+        let pairs = [(0, "Bob"), (1, "Cecil")];
+
+        // Bob goes down before getting a turn, so Cecil survives
+        let mut game = game_one_hit_from_the_end(&players, Resolution::Sequential)?;
+        let shots = game.play_round(sink_only(&pairs));
+        assert_eq!(shots.len(), 3);
+        assert_eq!(game.opponents(0), vec![2]);
+        assert!(game.result().is_none());
+
+        // Bob still fires in the round of going down, so Alice wins
+        let mut game = game_one_hit_from_the_end(&players, Resolution::Simultaneous)?;
+        let shots = game.play_round(sink_only(&pairs));
+        // Bob and Cecil aim at the same point on Alice's grid, which is only hit once
+        assert_eq!(shots.len(), 5);
+        assert!(game.opponents(0).is_empty());
+        assert!(matches!(game.result(), Some(GameResult::Winner(p)) if p.name == "Alice"));
+        Ok(())
+    }
+
+    #[test]
+    fn end_in_draw() -> Result<()> {
+        let mut game = game_one_hit_from_the_end(&["Alice", "Bob"], Resolution::Sequential)?;
+        assert!(game.result().is_none());
+
+        game.end_in_draw(DrawReason::RoundLimit);
+
+        assert!(matches!(
+            game.result(),
+            Some(GameResult::Draw(DrawReason::RoundLimit))
+        ));
+
+        // A game that is already won stays won
+        let mut game = game_one_hit_from_the_end(&["Alice", "Bob"], Resolution::Sequential)?;
+        game.fire(0, 1, Point(1, 0));
+        game.end_in_draw(DrawReason::Stalemate);
+        assert!(matches!(game.result(), Some(GameResult::Winner(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn opponents_are_other_players_alive() -> Result<()> {
         let mut new_game = Game::new(10);
         let alice = new_game.add_player("Alice");
         alice.place_ships_randomly()?;
@@ -144,20 +671,15 @@ mod tests {
         let cecil = new_game.add_player("Cecil");
         cecil.place_ships_randomly()?;
 
-        let game = new_game.start()?;
-        let turns = game.round();
+        let mut game = new_game.start()?;
+        assert_eq!(game.opponents(0), [1, 2]);
+        assert_eq!(game.opponents(1), [0, 2]);
+        assert_eq!(game.opponents(2), [0, 1]);
 
-        assert_eq!(turns.len(), game.players.len());
-        assert_eq!(*turns[0].player, game.players[0]);
-        assert_eq!(*turns[0].opponents[0], game.players[1]);
-        assert_eq!(*turns[0].opponents[1], game.players[2]);
-        assert_eq!(*turns[1].player, game.players[1]);
-        assert_eq!(*turns[1].opponents[0], game.players[0]);
-        assert_eq!(*turns[1].opponents[1], game.players[2]);
-        assert_eq!(*turns[2].player, game.players[2]);
-        assert_eq!(*turns[2].opponents[0], game.players[0]);
-        assert_eq!(*turns[2].opponents[1], game.players[1]);
+        game.players[1].forfeit();
 
+        assert_eq!(game.opponents(0), [2]);
+        assert_eq!(game.opponents(2), [0]);
         Ok(())
     }
 }
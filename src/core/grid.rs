@@ -1,42 +1,125 @@
-use rand::{thread_rng, Rng};
-use std::{cell::RefCell, fmt};
+use eyre::{eyre, Report, Result};
+use rand::Rng;
+use std::{fmt, str::FromStr};
 
-use super::Ship;
+use super::{Direction, Hull, Ship};
 
-#[derive(Debug, Eq, PartialEq)]
+/// A square grid of cells, kept as one bit per cell for where the ships are and where
+/// shots were fired, so that simulations can play many games fast.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Grid {
     pub size: usize,
-    cells: Vec<Vec<Cell>>,
+    occupied: Bits,
+    fired: Bits,
+
+    /// The index into `hulls` of the ship on each cell, if the cell is occupied.
+    ship_ids: Vec<u8>,
+    hulls: Vec<Hull>,
 }
 
 impl Grid {
     pub fn new(size: usize) -> Self {
         Grid {
             size,
-            cells: vec![vec![Cell::new(); size]; size],
+            occupied: Bits::new(size * size),
+            fired: Bits::new(size * size),
+            ship_ids: vec![0; size * size],
+            hulls: vec![],
         }
     }
 
-    pub fn cells(&self) -> CellIter {
-        CellIter::new(&self.cells)
-    }
-
-    pub fn at(&self, point: Point) -> Option<&Cell> {
+    fn index(&self, point: Point) -> Option<usize> {
         if (point.0 >= self.size) || (point.1 >= self.size) {
             return None;
         }
-        Some(&self.cells[point.0][point.1])
+        Some(point.1 * self.size + point.0)
+    }
+
+    /// The ships on the grid, in the order they were placed.
+    pub fn hulls(&self) -> &[Hull] {
+        &self.hulls
+    }
+
+    /// Puts a ship on the grid, without checking that it is in bounds or clear of
+    /// other ships, see `Player::place_ship`.
+    pub fn place_ship(&mut self, ship: Ship, origin: Point, direction: Direction) {
+        let hull = Hull::new(ship, origin, direction);
+        let id = self.hulls.len() as u8;
+        for point in hull.points() {
+            if let Some(i) = self.index(point) {
+                self.occupied.set(i);
+                self.ship_ids[i] = id;
+            }
+        }
+        self.hulls.push(hull);
     }
 
-    pub fn random_point(&self) -> Point {
-        let mut rng = thread_rng();
+    /// All cells of the grid, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.points().filter_map(|point| self.at(point))
+    }
+
+    pub fn at(&self, point: Point) -> Option<Cell> {
+        let i = self.index(point)?;
+        let ship = match self.occupied.get(i) {
+            true => Some(self.hulls[self.ship_ids[i] as usize].ship),
+            false => None,
+        };
+        Some(Cell {
+            ship,
+            hit: self.fired.get(i),
+        })
+    }
+
+    /// All points of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let size = self.size;
+        (0..size).flat_map(move |y| (0..size).map(move |x| Point(x, y)))
+    }
+
+    pub fn random_point(&self, rng: &mut impl Rng) -> Point {
         Point(rng.gen_range(0..self.size), rng.gen_range(0..self.size))
     }
 
-    pub fn random_cell(&self) -> (Point, &Cell) {
-        let point = self.random_point();
+    pub fn random_cell(&self, rng: &mut impl Rng) -> (Point, Cell) {
+        let point = self.random_point(rng);
         (point, self.at(point).unwrap())
     }
+
+    /// Fires at the given point, telling if a ship was sunk by the shot.
+    pub fn fire_at(&mut self, point: Point) -> Option<Fire> {
+        let i = self.index(point)?;
+        if self.fired.get(i) {
+            return None;
+        }
+        self.fired.set(i);
+        if !self.occupied.get(i) {
+            return Some(Fire::Miss);
+        }
+        let hull = &mut self.hulls[self.ship_ids[i] as usize];
+        hull.hits += 1;
+        match hull.is_sunk() {
+            true => Some(Fire::Sunk(hull.ship)),
+            false => Some(Fire::Hit),
+        }
+    }
+
+    /// Returns a copy of the grid with the same ships, but only the given points hit.
+    pub fn with_hits(&self, points: impl IntoIterator<Item = Point>) -> Grid {
+        let mut grid = Grid {
+            fired: Bits::new(self.size * self.size),
+            hulls: self
+                .hulls
+                .iter()
+                .map(|hull| Hull { hits: 0, ..*hull })
+                .collect(),
+            ..self.clone()
+        };
+        for point in points {
+            grid.fire_at(point);
+        }
+        grid
+    }
 }
 
 impl fmt::Display for Grid {
@@ -61,98 +144,77 @@ impl fmt::Display for Grid {
     }
 }
 
+/// One bit for each cell of a grid, row by row.
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Cell {
-    ship: RefCell<Option<Ship>>,
-    hit: RefCell<bool>,
-}
+struct Bits(Vec<u64>);
 
-impl Cell {
-    fn new() -> Self {
-        Cell {
-            ship: RefCell::new(None),
-            hit: RefCell::new(false),
-        }
+impl Bits {
+    fn new(len: usize) -> Self {
+        Bits(vec![0; len.div_ceil(64)])
     }
 
-    pub fn has_ship(&self) -> Option<Ship> {
-        self.ship.borrow().to_owned()
+    fn get(&self, i: usize) -> bool {
+        self.0[i / 64] & (1 << (i % 64)) != 0
     }
 
-    pub fn is_hit(&self) -> bool {
-        *self.hit.borrow()
-    }
-
-    pub fn place_ship(&self, ship: Ship) {
-        *self.ship.borrow_mut() = Some(ship);
-    }
-
-    pub fn fire(&self) -> Option<Fire> {
-        if self.is_hit() {
-            return None;
-        }
-        *self.hit.borrow_mut() = true;
-        match self.has_ship() {
-            Some(_ship) => {
-                // TODO Detect if entire ship is hit, and if so return Sunk(ship)
-                return Some(Fire::Hit);
-            }
-            None => {
-                return Some(Fire::Miss);
-            }
-        }
+    fn set(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
     }
 }
 
-pub struct CellIter<'a> {
-    cells: &'a Vec<Vec<Cell>>,
-    row_index: usize,
-    column_index: usize,
+/// What is at a point of the grid, see `Grid::at`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cell {
+    ship: Option<Ship>,
+    hit: bool,
 }
 
-impl<'a> CellIter<'a> {
-    fn new(cells: &'a Vec<Vec<Cell>>) -> Self {
-        Self {
-            cells,
-            row_index: 0,
-            column_index: 0,
-        }
+impl Cell {
+    pub fn has_ship(&self) -> Option<Ship> {
+        self.ship
     }
-}
-
-impl<'a> Iterator for CellIter<'a> {
-    type Item = &'a Cell;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        // Check if the current row is exhausted
-        while self.row_index < self.cells.len()
-            && self.column_index >= self.cells[self.row_index].len()
-        {
-            self.row_index += 1;
-            self.column_index = 0;
-        }
 
-        // Check if all rows are exhausted
-        if self.row_index >= self.cells.len() {
-            return None;
-        }
-
-        // Get the current item and move the iterator forward
-        let item = &self.cells[self.row_index][self.column_index];
-        self.column_index += 1;
-        Some(item)
+    pub fn is_hit(&self) -> bool {
+        self.hit
     }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Point(pub usize, pub usize);
 
+impl Point {
+    /// The largest grid that points can be written out for, with columns A to Z.
+    pub const MAX_GRID_SIZE: usize = 26;
+}
+
 impl fmt::Display for Point {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}{}", (65u8 + self.0 as u8) as char, self.1 + 1)
     }
 }
 
+impl FromStr for Point {
+    type Err = Report;
+
+    /// Parses coordinates like "C5", the same way as they are formatted.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || eyre!("Invalid coordinate: {}", s);
+        let mut chars = s.trim().chars();
+        let x = chars
+            .next()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| (c.to_ascii_uppercase() as u8 - b'A') as usize)
+            .ok_or_else(invalid)?;
+        let y = chars
+            .as_str()
+            .parse::<usize>()
+            .ok()
+            .filter(|y| (1..=Self::MAX_GRID_SIZE).contains(y))
+            .ok_or_else(invalid)?;
+        Ok(Point(x, y - 1))
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Fire {
     Miss,
@@ -162,6 +224,8 @@ pub enum Fire {
 
 #[cfg(test)]
 mod tests {
+    use rand::thread_rng;
+
     use super::*;
 
     #[test]
@@ -175,13 +239,54 @@ mod tests {
         let grid_size = 10;
         let grid = Grid::new(grid_size);
 
-        let (point, cell) = grid.random_cell();
+        let (point, cell) = grid.random_cell(&mut thread_rng());
 
         assert!(point.0 < grid_size);
         assert!(point.1 < grid_size);
         assert_eq!(grid.at(point).unwrap(), cell);
     }
 
+    #[test]
+    fn with_hits() {
+        let mut grid = Grid::new(3);
+        grid.place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal);
+        grid.fire_at(Point(0, 0));
+        grid.fire_at(Point(2, 2));
+
+        let copy = grid.with_hits([Point(1, 0), Point(1, 1)]);
+
+        assert_eq!(copy.to_string(), ["OX.", "._.", "..."].join("\n"));
+        assert_eq!(copy.hulls()[0].hits, 1);
+        assert_eq!(grid.to_string(), ["XO.", "...", ".._"].join("\n"));
+    }
+
+    #[test]
+    fn fire_at_sinks_ship() {
+        let mut grid = Grid::new(3);
+        grid.place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal);
+
+        assert_eq!(grid.fire_at(Point(0, 1)), Some(Fire::Miss));
+        assert_eq!(grid.fire_at(Point(0, 0)), Some(Fire::Hit));
+        assert!(grid.hulls()[0].is_damaged());
+        assert_eq!(grid.fire_at(Point(1, 0)), Some(Fire::Sunk(Ship::Destroyer)));
+        assert!(grid.hulls()[0].is_sunk());
+        assert_eq!(grid.fire_at(Point(1, 0)), None);
+        assert_eq!(grid.fire_at(Point(3, 0)), None);
+    }
+
+    #[test]
+    fn largest_grid() {
+        let size = Point::MAX_GRID_SIZE;
+        let mut grid = Grid::new(size);
+        grid.place_ship(Ship::Carrier, Point(size - 1, 21), Direction::Vertical);
+
+        assert_eq!(grid.fire_at(Point(size - 1, size - 1)), Some(Fire::Hit));
+        assert_eq!(grid.fire_at(Point(0, size - 1)), Some(Fire::Miss));
+        assert_eq!(grid.cells().filter(|c| c.is_hit()).count(), 2);
+        assert_eq!(grid.cells().filter(|c| c.has_ship().is_some()).count(), 5);
+        assert!(!grid.at(Point(size - 2, size - 1)).unwrap().is_hit());
+    }
+
     #[test]
     fn point_format() {
         assert_eq!(Point(0, 0).to_string(), "A1");
@@ -189,4 +294,15 @@ mod tests {
         assert_eq!(Point(1, 0).to_string(), "B1");
         assert_eq!(Point(2, 4).to_string(), "C5");
     }
+
+    #[test]
+    fn point_from_str() {
+        assert_eq!("A1".parse::<Point>().unwrap(), Point(0, 0));
+        assert_eq!("c5".parse::<Point>().unwrap(), Point(2, 4));
+        assert_eq!("J10".parse::<Point>().unwrap(), Point(9, 9));
+        assert_eq!("Z26".parse::<Point>().unwrap(), Point(25, 25));
+        for s in ["", "C", "5", "5C", "C0", "C27", "C-1", "CC5", "C5x", "Ø5"] {
+            assert!(s.parse::<Point>().is_err(), "{} should not parse", s);
+        }
+    }
 }
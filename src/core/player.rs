@@ -1,15 +1,30 @@
-use std::cell::RefCell;
-
 use eyre::{eyre, Result};
+use rand::{thread_rng, Rng};
+
+use super::{grid::Fire, Active, Direction, Grid, New, Point, Rules, Ship, Variant};
 
-use super::{grid::Fire, Active, Direction, Grid, New, Point, Ship};
+/// How many times the remaining fleet is placed before giving up, as the first ships
+/// placed may leave no room for the rest.
+const FLEET_ATTEMPTS: usize = 100;
 
 #[derive(Debug, Eq, PartialEq)]
 pub struct Player<Stage> {
     stage: std::marker::PhantomData<Stage>,
     pub name: String,
-    pub to_place: RefCell<Vec<Ship>>,
+    pub to_place: Vec<Ship>,
     pub grid: Grid,
+    variant: Variant,
+    forfeited: bool,
+    tally: Tally,
+}
+
+/// What the player has taken so far, kept up to date as the player is fired at.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Tally {
+    ships: usize,
+    ships_sunk: usize,
+    cells_remaining: usize,
+    shots_received: usize,
 }
 
 impl Default for Player<Active> {
@@ -18,50 +33,56 @@ impl Default for Player<Active> {
         Self {
             stage: std::marker::PhantomData,
             name: "Default".into(),
-            to_place: RefCell::new(vec![]),
+            to_place: vec![],
             grid: Grid::new(10),
+            variant: Variant::default(),
+            forfeited: false,
+            tally: Tally::default(),
         }
     }
 }
 
 impl Player<New> {
     pub fn new(name: &str, grid_size: usize) -> Player<New> {
+        Self::with_rules(name, grid_size, &Rules::for_grid(grid_size))
+    }
+
+    pub fn with_rules(name: &str, grid_size: usize, rules: &Rules) -> Player<New> {
         Self {
             stage: std::marker::PhantomData,
             name: name.to_string(),
-            to_place: RefCell::new(Ship::for_grid(grid_size)),
+            to_place: rules.fleet.clone(),
             grid: Grid::new(grid_size),
+            variant: rules.variant,
+            forfeited: false,
+            tally: Tally::default(),
         }
     }
 
     pub fn get_ship_to_place(&self) -> Option<Ship> {
-        match self.to_place.borrow().first() {
-            Some(ship) => Some(ship.clone()),
-            None => None,
-        }
+        self.to_place.first().copied()
     }
 
     fn get_place_ship_index(&self, ship: Ship) -> Result<usize> {
         self.to_place
-            .borrow()
             .iter()
             .position(|s| *s == ship)
             .ok_or_else(|| {
                 eyre!(
                     "{} is not to be placed. Expected one of {:?}",
                     ship,
-                    self.to_place.borrow()
+                    self.to_place
                 )
             })
     }
 
-    fn remove_ship_to_place(&self, ship: Ship) -> Result<()> {
+    fn remove_ship_to_place(&mut self, ship: Ship) -> Result<()> {
         let index = self.get_place_ship_index(ship)?;
-        self.to_place.borrow_mut().remove(index);
+        self.to_place.remove(index);
         Ok(())
     }
 
-    pub fn place_ship(&self, ship: Ship, point: Point, direction: Direction) -> Result<()> {
+    pub fn place_ship(&mut self, ship: Ship, point: Point, direction: Direction) -> Result<()> {
         let (step_x, step_y) = direction.step();
 
         // Check that ship is to be placed
@@ -76,34 +97,64 @@ impl Player<New> {
                     if let Some(existing_ship) = cell.has_ship() {
                         return Err(eyre!("{} overlaps with {}", ship, existing_ship));
                     }
+                    if !self.variant.allows_touching() {
+                        if let Some(existing_ship) = self.neighbouring_ship(point_i) {
+                            return Err(eyre!("{} touches {}", ship, existing_ship));
+                        }
+                    }
                 }
             }
         }
 
-        // Actually place the ship
-        for i in 0..ship.length() {
-            if let Some(cell) = self
-                .grid
-                .at(Point(point.0 + i * step_x, point.1 + i * step_y))
-            {
-                cell.place_ship(ship);
-            }
-        }
+        self.grid.place_ship(ship, point, direction);
         self.remove_ship_to_place(ship)?;
 
         Ok(())
     }
 
-    pub fn place_ships_randomly(&self) -> Result<()> {
+    fn neighbouring_ship(&self, point: Point) -> Option<Ship> {
+        let xs = point.0.saturating_sub(1)..=point.0 + 1;
+        xs.flat_map(|x| (point.1.saturating_sub(1)..=point.1 + 1).map(move |y| Point(x, y)))
+            .filter_map(|p| self.grid.at(p))
+            .find_map(|cell| cell.has_ship())
+    }
+
+    pub fn place_ships_randomly(&mut self) -> Result<()> {
+        self.place_ships_with(&mut thread_rng())
+    }
+
+    /// Places the remaining ships at random, as decided by the given generator. If the
+    /// ships placed first leave no room for the rest, they are all placed again.
+    pub fn place_ships_with(&mut self, rng: &mut impl Rng) -> Result<()> {
+        let (grid, to_place) = (self.grid.clone(), self.to_place.clone());
+        let mut result = self.place_each_ship(rng);
+        for _ in 1..FLEET_ATTEMPTS {
+            if result.is_ok() {
+                break;
+            }
+            self.grid = grid.clone();
+            self.to_place = to_place.clone();
+            result = self.place_each_ship(rng);
+        }
+        result
+    }
+
+    fn place_each_ship(&mut self, rng: &mut impl Rng) -> Result<()> {
+        let max_attempts = 100 * self.grid.size * self.grid.size;
         while let Some(ship) = self.get_ship_to_place() {
+            let mut attempts = 0;
             loop {
-                let point = self.grid.random_point();
-                match self.place_ship(ship, point, Direction::random()) {
+                let point = self.grid.random_point(rng);
+                match self.place_ship(ship, point, Direction::random(rng)) {
                     Ok(_) => {
                         break;
                     }
-                    Err(_) => {
+                    Err(_) if attempts < max_attempts => {
                         // Try again
+                        attempts += 1;
+                    }
+                    Err(_) => {
+                        return Err(eyre!("Found no room for {}", ship));
                     }
                 }
             }
@@ -112,7 +163,7 @@ impl Player<New> {
     }
 
     pub fn is_ready(&self) -> bool {
-        self.to_place.borrow().is_empty()
+        self.to_place.is_empty()
     }
 
     pub fn ready(self) -> Result<Player<Active>> {
@@ -124,49 +175,70 @@ impl Player<New> {
             ));
         }
 
+        let hulls = self.grid.hulls();
+        let tally = Tally {
+            ships: hulls.len(),
+            cells_remaining: hulls.iter().map(|hull| hull.length()).sum(),
+            ..Tally::default()
+        };
         Ok(Player {
             stage: std::marker::PhantomData,
             name: self.name,
             to_place: self.to_place,
             grid: self.grid,
+            variant: self.variant,
+            forfeited: false,
+            tally,
         })
     }
 }
 
 impl Player<Active> {
-    pub fn fire_at(&self, point: Point) -> Option<Fire> {
-        self.grid.at(point).and_then(|cell| cell.fire())
-    }
-
-    pub fn fire_at_random(&self) -> Option<(Point, Fire)> {
-        let max_attempts = self.grid.size * self.grid.size;
-        for _ in 0..max_attempts {
-            let (point, cell) = self.grid.random_cell();
-            match cell.is_hit() {
-                true => {
-                    // Select a new cell to hit
-                }
-                false => {
-                    return Some((point, cell.fire().unwrap()));
-                }
+    pub(crate) fn fire_at(&mut self, point: Point) -> Option<Fire> {
+        let fire = self.grid.fire_at(point)?;
+        let tally = &mut self.tally;
+        tally.shots_received += 1;
+        match fire {
+            Fire::Miss => {}
+            Fire::Hit => tally.cells_remaining -= 1,
+            Fire::Sunk(_) => {
+                tally.cells_remaining -= 1;
+                tally.ships_sunk += 1;
             }
         }
-        None
+        Some(fire)
+    }
+
+    /// The number of ships in the fleet.
+    pub fn ships(&self) -> usize {
+        self.tally.ships
+    }
+
+    pub fn ships_sunk(&self) -> usize {
+        self.tally.ships_sunk
+    }
+
+    pub fn ships_afloat(&self) -> usize {
+        self.ships() - self.ships_sunk()
     }
 
-    pub fn num_ships_total(&self) -> usize {
-        self.grid.cells().filter(|p| p.has_ship().is_some()).count()
+    /// The number of ship cells that have not been hit yet.
+    pub fn cells_remaining(&self) -> usize {
+        self.tally.cells_remaining
     }
 
-    pub fn num_ships_alive(&self) -> usize {
-        self.grid
-            .cells()
-            .filter(|p| p.has_ship().is_some() && !p.is_hit())
-            .count()
+    /// The number of shots fired at the player, hits and misses alike.
+    pub fn shots_received(&self) -> usize {
+        self.tally.shots_received
+    }
+
+    /// Gives up the game, which eliminates the player even if ships remain.
+    pub fn forfeit(&mut self) {
+        self.forfeited = true;
     }
 
     pub fn is_alive(&self) -> bool {
-        self.num_ships_alive() > 0
+        !self.forfeited && self.ships_afloat() > 0
     }
 }
 
@@ -174,13 +246,13 @@ impl Player<Active> {
 mod tests {
     use eyre::Result;
 
-    use crate::core::{Direction, Ship};
+    use crate::core::{Direction, Resolution, Ship};
 
     use super::*;
 
     #[test]
     fn place_ship_horizontal() -> Result<()> {
-        let player = Player::new("Alice", 3);
+        let mut player = Player::new("Alice", 3);
 
         player.place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal)?;
 
@@ -190,7 +262,7 @@ mod tests {
 
     #[test]
     fn place_ship_vertical() -> Result<()> {
-        let player = Player::new("Alice", 3);
+        let mut player = Player::new("Alice", 3);
 
         player.place_ship(Ship::Destroyer, Point(1, 1), Direction::Vertical)?;
 
@@ -200,7 +272,7 @@ mod tests {
 
     #[test]
     fn place_ship_out_of_bounds() -> Result<()> {
-        let player = Player::new("Alice", 10);
+        let mut player = Player::new("Alice", 10);
 
         // When a destroyer of length two is placed on the last cell on a row
         let result = player.place_ship(Ship::Destroyer, Point(9, 0), Direction::Horizontal);
@@ -211,7 +283,7 @@ mod tests {
 
     #[test]
     fn place_ship_overlapping_existing_ship() -> Result<()> {
-        let player = Player::new("Alice", 10);
+        let mut player = Player::new("Alice", 10);
         // Given a carrier in the first five cells: CCCCC.....
         player.place_ship(Ship::Carrier, Point(0, 0), Direction::Horizontal)?;
 
@@ -224,7 +296,7 @@ mod tests {
 
     #[test]
     fn place_same_ship_twice() -> Result<()> {
-        let player = Player::new("Alice", 10);
+        let mut player = Player::new("Alice", 10);
         player.place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal)?;
 
         let result = player.place_ship(Ship::Destroyer, Point(0, 1), Direction::Horizontal);
@@ -233,12 +305,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn place_ship_touching_existing_ship() -> Result<()> {
+        let rules = Rules {
+            fleet: vec![Ship::Cruiser, Ship::Destroyer],
+            variant: Variant::NoTouch,
+            shot_clock: None,
+            resolution: Resolution::Sequential,
+        };
+        let mut player = Player::with_rules("Alice", 10, &rules);
+        player.place_ship(Ship::Cruiser, Point(0, 0), Direction::Horizontal)?;
+
+        // Diagonally adjacent to the cruiser
+        let result = player.place_ship(Ship::Destroyer, Point(3, 1), Direction::Horizontal);
+        assert!(result.is_err());
+
+        // One empty cell between the ships
+        player.place_ship(Ship::Destroyer, Point(4, 0), Direction::Horizontal)?;
+        Ok(())
+    }
+
+    #[test]
+    fn place_tight_fleet_randomly() -> Result<()> {
+        // Most attempts to place these ships leave no room for the last ones
+        let rules = Rules {
+            fleet: vec![
+                Ship::Battleship,
+                Ship::Cruiser,
+                Ship::Submarine,
+                Ship::Destroyer,
+            ],
+            variant: Variant::NoTouch,
+            ..Rules::for_grid(5)
+        };
+        for _ in 0..10 {
+            let mut player = Player::with_rules("Alice", 5, &rules);
+            player.place_ships_randomly()?;
+            assert!(player.is_ready());
+        }
+        Ok(())
+    }
+
     #[test]
     fn fire_at() -> Result<()> {
         // Given a carrier: CCCCC.....
-        let new_player = Player::new("Alice", 2);
+        let mut new_player = Player::new("Alice", 2);
         new_player.place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal)?;
-        let player = new_player.ready()?;
+        let mut player = new_player.ready()?;
 
         // CC/x. is a miss
         assert_eq!(player.fire_at(Point(0, 1)), Some(Fire::Miss));
@@ -248,12 +361,18 @@ mod tests {
 
         // Another hit in the same spot does not count as a move
         assert_eq!(player.fire_at(Point(0, 0)), None);
+
+        // XX/x. sinks the destroyer
+        assert_eq!(
+            player.fire_at(Point(1, 0)),
+            Some(Fire::Sunk(Ship::Destroyer))
+        );
         Ok(())
     }
 
     #[test]
     fn status_checks_if_any_ships_remain() -> Result<()> {
-        let new_player = Player::new("Alice", 3);
+        let mut new_player = Player::new("Alice", 3);
         new_player.place_ship(Ship::Submarine, Point(0, 0), Direction::Horizontal)?;
 
         // There are more ships to place
@@ -263,7 +382,7 @@ mod tests {
         new_player.place_ship(Ship::Destroyer, Point(0, 2), Direction::Horizontal)?;
 
         // All ships have been placed
-        let player = new_player.ready()?;
+        let mut player = new_player.ready()?;
 
         // A miss
         player.fire_at(Point(2, 2));
@@ -288,4 +407,39 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn counts_follow_shots() -> Result<()> {
+        let mut new_player = Player::new("Alice", 3);
+        new_player.place_ship(Ship::Cruiser, Point(0, 0), Direction::Horizontal)?;
+        new_player.place_ship(Ship::Destroyer, Point(0, 1), Direction::Horizontal)?;
+        new_player.place_ship(Ship::Submarine, Point(0, 2), Direction::Horizontal)?;
+        let mut player = new_player.ready()?;
+        assert_eq!(player.ships(), 3);
+        assert_eq!(player.cells_remaining(), 8);
+
+        player.fire_at(Point(2, 1));
+        player.fire_at(Point(0, 1));
+        player.fire_at(Point(0, 1));
+        player.fire_at(Point(1, 1));
+
+        assert_eq!(player.shots_received(), 3);
+        assert_eq!(player.ships_sunk(), 1);
+        assert_eq!(player.ships_afloat(), 2);
+        assert_eq!(player.cells_remaining(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn forfeit_eliminates_player() -> Result<()> {
+        let mut new_player = Player::new("Alice", 2);
+        new_player.place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal)?;
+        let mut player = new_player.ready()?;
+        assert!(player.is_alive());
+
+        player.forfeit();
+
+        assert!(!player.is_alive());
+        Ok(())
+    }
 }
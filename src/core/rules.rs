@@ -0,0 +1,195 @@
+use std::fmt;
+
+use eyre::{eyre, Result};
+
+use super::{Point, Ship, ShotClock};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rules {
+    pub fleet: Vec<Ship>,
+    pub variant: Variant,
+    pub shot_clock: Option<ShotClock>,
+    pub resolution: Resolution,
+}
+
+impl Rules {
+    pub fn for_grid(grid_size: usize) -> Self {
+        Self {
+            fleet: Ship::for_grid(grid_size),
+            variant: Variant::default(),
+            shot_clock: None,
+            resolution: Resolution::default(),
+        }
+    }
+
+    pub fn validate(&self, grid_size: usize) -> Result<()> {
+        if grid_size > Point::MAX_GRID_SIZE {
+            return Err(eyre!(
+                "The grid can be at most {}x{}.",
+                Point::MAX_GRID_SIZE,
+                Point::MAX_GRID_SIZE
+            ));
+        }
+        if self.fleet.is_empty() {
+            return Err(eyre!("The fleet must have at least one ship."));
+        }
+        for (i, ship) in self.fleet.iter().enumerate() {
+            if self.fleet[..i].contains(ship) {
+                return Err(eyre!("{} is in the fleet more than once.", ship));
+            }
+            if ship.length() > grid_size {
+                return Err(eyre!(
+                    "{} of length {} does not fit on a {}x{} grid.",
+                    ship,
+                    ship.length(),
+                    grid_size,
+                    grid_size
+                ));
+            }
+        }
+        if self.shot_clock.is_some_and(|c| c.limit.is_zero()) {
+            return Err(eyre!("The shot clock must allow some time to fire."));
+        }
+        let fleet_cells: usize = self.fleet.iter().map(|s| s.length()).sum();
+        if fleet_cells > grid_size * grid_size {
+            return Err(eyre!(
+                "A fleet of {} cells does not fit on a {}x{} grid.",
+                fleet_cells,
+                grid_size,
+                grid_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fleet = self
+            .fleet
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "{} with {}", self.variant, fleet)?;
+        if let Some(shot_clock) = self.shot_clock {
+            write!(f, " ({})", shot_clock)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Variant {
+    /// Ships may be placed next to each other.
+    #[default]
+    Classic,
+
+    /// Ships may not touch each other, not even diagonally.
+    NoTouch,
+}
+
+impl Variant {
+    pub fn allows_touching(&self) -> bool {
+        match self {
+            Self::Classic => true,
+            Self::NoTouch => false,
+        }
+    }
+}
+
+/// How the shots of a round are resolved, see `Game::play_round`.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Resolution {
+    /// Players fire one after the other, and an eliminated player loses the rest of
+    /// their turns, so the last player standing always wins.
+    #[default]
+    Sequential,
+
+    /// All shots of a round resolve together, so players still fire in the round they
+    /// are eliminated, and the last players may go down together in a draw.
+    Simultaneous,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_are_valid() {
+        for grid_size in 2..=10 {
+            assert!(Rules::for_grid(grid_size).validate(grid_size).is_ok());
+        }
+    }
+
+    #[test]
+    fn empty_fleet_is_invalid() {
+        let rules = Rules {
+            fleet: vec![],
+            variant: Variant::Classic,
+            shot_clock: None,
+            resolution: Resolution::Sequential,
+        };
+
+        assert!(rules.validate(10).is_err());
+    }
+
+    #[test]
+    fn fleet_with_duplicate_ships_is_invalid() {
+        let rules = Rules {
+            fleet: vec![Ship::Destroyer, Ship::Destroyer],
+            variant: Variant::Classic,
+            shot_clock: None,
+            resolution: Resolution::Sequential,
+        };
+
+        assert!(rules.validate(10).is_err());
+    }
+
+    #[test]
+    fn fleet_that_does_not_fit_is_invalid() {
+        let rules = Rules {
+            fleet: vec![Ship::Carrier],
+            variant: Variant::Classic,
+            shot_clock: None,
+            resolution: Resolution::Sequential,
+        };
+
+        assert!(rules.validate(4).is_err());
+    }
+
+    #[test]
+    fn grid_without_coordinates_is_invalid() {
+        assert!(Rules::for_grid(26).validate(26).is_ok());
+        assert!(Rules::for_grid(27).validate(27).is_err());
+    }
+
+    #[test]
+    fn variant_from_str() {
+        assert_eq!("classic".parse::<Variant>().unwrap(), Variant::Classic);
+        assert_eq!("no-touch".parse::<Variant>().unwrap(), Variant::NoTouch);
+        assert!("salvo".parse::<Variant>().is_err());
+    }
+}
@@ -1,7 +1,21 @@
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use strum::IntoEnumIterator;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, strum_macros::EnumIter, strum_macros::Display)]
+use super::Point;
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(ascii_case_insensitive)]
 pub enum Ship {
     Carrier,
     Battleship,
@@ -26,15 +40,14 @@ impl Ship {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Direction {
     Horizontal,
     Vertical,
 }
 
 impl Direction {
-    pub fn random() -> Self {
-        let mut rng = thread_rng();
+    pub fn random(rng: &mut impl Rng) -> Self {
         match rng.gen_bool(0.5) {
             true => Direction::Horizontal,
             false => Direction::Vertical,
@@ -49,10 +62,66 @@ impl Direction {
     }
 }
 
+/// A ship placed on a grid, and how many times it has been hit.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Hull {
+    pub ship: Ship,
+    pub origin: Point,
+    pub direction: Direction,
+    pub hits: usize,
+}
+
+impl Hull {
+    pub fn new(ship: Ship, origin: Point, direction: Direction) -> Self {
+        Self {
+            ship,
+            origin,
+            direction,
+            hits: 0,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.ship.length()
+    }
+
+    /// The points the ship covers, from the origin.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (step_x, step_y) = self.direction.step();
+        let Point(x, y) = self.origin;
+        (0..self.length()).map(move |i| Point(x + i * step_x, y + i * step_y))
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.points().any(|p| p == point)
+    }
+
+    pub fn is_sunk(&self) -> bool {
+        self.hits >= self.length()
+    }
+
+    /// Hit, but still afloat.
+    pub fn is_damaged(&self) -> bool {
+        self.hits > 0 && !self.is_sunk()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn hull_points() {
+        let hull = Hull::new(Ship::Cruiser, Point(2, 1), Direction::Vertical);
+
+        let points = hull.points().collect::<Vec<_>>();
+
+        assert_eq!(points, [Point(2, 1), Point(2, 2), Point(2, 3)]);
+        assert!(hull.contains(Point(2, 3)));
+        assert!(!hull.contains(Point(3, 1)));
+        assert!(!hull.is_damaged());
+    }
+
     #[test]
     fn ships_for_grid() {
         assert_eq!(
@@ -91,4 +160,11 @@ mod tests {
         assert_eq!(Ship::for_grid(2), vec![Ship::Destroyer]);
         assert_eq!(Ship::for_grid(1), vec![]);
     }
+
+    #[test]
+    fn ship_from_str() {
+        assert_eq!("Carrier".parse::<Ship>().unwrap(), Ship::Carrier);
+        assert_eq!("destroyer".parse::<Ship>().unwrap(), Ship::Destroyer);
+        assert!("dinghy".parse::<Ship>().is_err());
+    }
 }
@@ -0,0 +1,97 @@
+use super::{Fire, Shot};
+
+/// How well a player fired during a game.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub shots: usize,
+
+    /// Shots that hit a ship, including those that sunk it.
+    pub hits: usize,
+
+    /// The most hits in a row.
+    pub longest_streak: usize,
+
+    /// How many shots the player needed to sink a ship, if one was sunk.
+    pub shots_to_first_sink: Option<usize>,
+}
+
+impl Stats {
+    /// Works out the stats of the given player from the shots fired in a game.
+    pub fn new(shots: &[Shot], shooter: usize) -> Self {
+        let mut stats = Self::default();
+        let mut streak = 0;
+        for shot in shots.iter().filter(|shot| shot.shooter == shooter) {
+            stats.shots += 1;
+            match shot.fire {
+                Fire::Miss => streak = 0,
+                Fire::Hit | Fire::Sunk(_) => {
+                    stats.hits += 1;
+                    streak += 1;
+                    stats.longest_streak = stats.longest_streak.max(streak);
+                }
+            }
+            if let (Fire::Sunk(_), None) = (shot.fire, stats.shots_to_first_sink) {
+                stats.shots_to_first_sink = Some(stats.shots);
+            }
+        }
+        stats
+    }
+
+    /// The share of shots that hit, from 0 to 1.
+    pub fn accuracy(&self) -> f64 {
+        match self.shots {
+            0 => 0.0,
+            shots => self.hits as f64 / shots as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::{Point, Ship};
+
+    use super::*;
+
+    fn shot(shooter: usize, fire: Fire) -> Shot {
+        Shot {
+            shooter,
+            target: 1 - shooter,
+            point: Point(0, 0),
+            fire,
+        }
+    }
+
+    #[test]
+    fn stats_from_shots() {
+        let shots = [
+            shot(0, Fire::Hit),
+            shot(1, Fire::Hit),
+            shot(0, Fire::Miss),
+            shot(1, Fire::Hit),
+            shot(0, Fire::Hit),
+            shot(1, Fire::Sunk(Ship::Destroyer)),
+            shot(0, Fire::Hit),
+            shot(1, Fire::Miss),
+            shot(0, Fire::Sunk(Ship::Cruiser)),
+        ];
+
+        let stats = Stats::new(&shots, 0);
+        assert_eq!(stats.shots, 5);
+        assert_eq!(stats.hits, 4);
+        assert_eq!(stats.accuracy(), 0.8);
+        assert_eq!(stats.longest_streak, 3);
+        assert_eq!(stats.shots_to_first_sink, Some(5));
+
+        let stats = Stats::new(&shots, 1);
+        assert_eq!(stats.longest_streak, 3);
+        assert_eq!(stats.shots_to_first_sink, Some(3));
+    }
+
+    #[test]
+    fn no_shots() {
+        let stats = Stats::new(&[], 0);
+
+        assert_eq!(stats, Stats::default());
+        assert_eq!(stats.accuracy(), 0.0);
+    }
+}
@@ -0,0 +1,347 @@
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+use super::{Active, Direction, Grid, Player, Point, Ship};
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Strategy {
+    /// Fires at a random cell that has not been fired at before.
+    #[default]
+    Random,
+
+    /// Fires at random until it hits a ship, and then around the hits until the ship is sunk.
+    HuntTarget,
+
+    /// Fires where the ships afloat fit in the most ways, given the shots so far.
+    Density,
+
+    /// Knows where the ships are, and never misses.
+    Cheater,
+}
+
+impl Strategy {
+    /// Picks where to fire at the opponent next, see `Game::fire_with`.
+    pub fn pick(&self, opponent: &Player<Active>) -> Option<Point> {
+        self.pick_with(opponent, &mut thread_rng())
+    }
+
+    /// Picks where to fire next, breaking ties with the given generator.
+    pub fn pick_with(&self, opponent: &Player<Active>, rng: &mut impl Rng) -> Option<Point> {
+        let board = Board::new(&opponent.grid);
+        match self {
+            Self::Random => board.random(rng),
+            Self::HuntTarget => board.hunt_target(rng),
+            Self::Density => board.densest(rng).or_else(|| board.hunt_target(rng)),
+            Self::Cheater => board.cheat(rng),
+        }
+    }
+}
+
+/// How hard the bot is to beat, which decides its strategy.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Difficulty {
+    #[default]
+    Easy,
+    Medium,
+    Hard,
+
+    /// Never misses, for trying out the end of a game.
+    Cheater,
+}
+
+impl Difficulty {
+    pub fn strategy(&self) -> Strategy {
+        match self {
+            Self::Easy => Strategy::Random,
+            Self::Medium => Strategy::HuntTarget,
+            Self::Hard => Strategy::Density,
+            Self::Cheater => Strategy::Cheater,
+        }
+    }
+}
+
+/// What the shooter knows about a cell of the opponent's grid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Known {
+    Unknown,
+    Miss,
+
+    /// A hit on a ship that is still afloat.
+    Hit,
+
+    /// A hit on a ship that has been sunk.
+    Sunk,
+}
+
+/// The opponent's grid as seen by the shooter.
+struct Board<'a> {
+    grid: &'a Grid,
+    known: Vec<Known>,
+
+    /// The ships that have not been sunk, which the rules tell the shooter.
+    afloat: Vec<Ship>,
+}
+
+impl<'a> Board<'a> {
+    fn new(grid: &'a Grid) -> Self {
+        let afloat = grid
+            .hulls()
+            .iter()
+            .filter(|hull| !hull.is_sunk())
+            .map(|hull| hull.ship)
+            .collect::<Vec<_>>();
+        let known = grid
+            .points()
+            .map(|point| {
+                let cell = grid.at(point).unwrap();
+                match (cell.is_hit(), cell.has_ship()) {
+                    (false, _) => Known::Unknown,
+                    (true, None) => Known::Miss,
+                    (true, Some(ship)) if afloat.contains(&ship) => Known::Hit,
+                    (true, Some(_)) => Known::Sunk,
+                }
+            })
+            .collect();
+        Self {
+            grid,
+            known,
+            afloat,
+        }
+    }
+
+    fn at(&self, point: Point) -> Option<Known> {
+        let size = self.grid.size;
+        (point.0 < size && point.1 < size).then(|| self.known[point.1 * size + point.0])
+    }
+
+    fn step(&self, point: Point, (dx, dy): (isize, isize)) -> Option<Point> {
+        let point = Point(
+            point.0.checked_add_signed(dx)?,
+            point.1.checked_add_signed(dy)?,
+        );
+        self.at(point).map(|_| point)
+    }
+
+    fn points_where(&self, known: Known) -> Vec<Point> {
+        self.grid
+            .points()
+            .filter(|p| self.at(*p) == Some(known))
+            .collect()
+    }
+
+    fn random(&self, rng: &mut impl Rng) -> Option<Point> {
+        self.points_where(Known::Unknown).choose(rng).copied()
+    }
+
+    /// Fires next to hits, preferring to continue a line of hits, or else hunts on a
+    /// checkerboard pattern, which is enough to find every ship.
+    fn hunt_target(&self, rng: &mut impl Rng) -> Option<Point> {
+        let mut in_line = vec![];
+        let mut next_to_hit = vec![];
+        for hit in self.points_where(Known::Hit) {
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let Some(next) = self.step(hit, (dx, dy)) else {
+                    continue;
+                };
+                if self.at(next) != Some(Known::Unknown) {
+                    continue;
+                }
+                let previous = self.step(hit, (-dx, -dy)).and_then(|p| self.at(p));
+                match previous {
+                    Some(Known::Hit) => in_line.push(next),
+                    _ => next_to_hit.push(next),
+                }
+            }
+        }
+        let unknown = self.points_where(Known::Unknown);
+        let checkerboard = unknown
+            .iter()
+            .copied()
+            .filter(|p| (p.0 + p.1) % 2 == 0)
+            .collect::<Vec<_>>();
+        [in_line, next_to_hit, checkerboard, unknown]
+            .iter()
+            .find_map(|points| points.choose(rng).copied())
+    }
+
+    /// Counts the ways each ship afloat can be placed over each cell, and picks the cell
+    /// with the most. When there are hits, only placements through the hits count.
+    fn densest(&self, rng: &mut impl Rng) -> Option<Point> {
+        let size = self.grid.size;
+        let is_targeting = self.known.contains(&Known::Hit);
+        let mut density = vec![0; size * size];
+        for ship in &self.afloat {
+            for direction in [Direction::Horizontal, Direction::Vertical] {
+                let (step_x, step_y) = direction.step();
+                for origin in self.grid.points() {
+                    let points = (0..ship.length())
+                        .map(|i| Point(origin.0 + i * step_x, origin.1 + i * step_y))
+                        .collect::<Vec<_>>();
+                    let known = points.iter().map(|p| self.at(*p)).collect::<Vec<_>>();
+                    if known
+                        .iter()
+                        .any(|k| !matches!(k, Some(Known::Unknown | Known::Hit)))
+                    {
+                        continue; // Off the grid, or over a miss or a sunk ship
+                    }
+                    let hits = known.iter().filter(|k| **k == Some(Known::Hit)).count();
+                    let weight = match is_targeting {
+                        true => hits,
+                        false => 1,
+                    };
+                    for point in points {
+                        density[point.1 * size + point.0] += weight;
+                    }
+                }
+            }
+        }
+        let unknown = self.points_where(Known::Unknown);
+        let max = unknown
+            .iter()
+            .map(|p| density[p.1 * size + p.0])
+            .max()
+            .filter(|max| *max > 0)?;
+        let densest = unknown
+            .into_iter()
+            .filter(|p| density[p.1 * size + p.0] == max)
+            .collect::<Vec<_>>();
+        densest.choose(rng).copied()
+    }
+
+    /// Peeks at the opponent's ships.
+    fn cheat(&self, rng: &mut impl Rng) -> Option<Point> {
+        let ships = self
+            .points_where(Known::Unknown)
+            .into_iter()
+            .filter(|p| self.grid.at(*p).is_some_and(|c| c.has_ship().is_some()))
+            .collect::<Vec<_>>();
+        ships.choose(rng).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use eyre::Result;
+
+    use crate::core::{Direction, Fire, Player, Rules, Ship};
+
+    use super::*;
+
+    #[test]
+    fn random_fires_at_unhit_cell() -> Result<()> {
+        let mut new_player = Player::new("Alice", 2);
+        new_player.place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal)?;
+        let mut player = new_player.ready()?;
+        player.fire_at(Point(0, 0));
+        player.fire_at(Point(1, 0));
+        player.fire_at(Point(0, 1));
+
+        assert_eq!(Strategy::Random.pick(&player), Some(Point(1, 1)));
+        player.fire_at(Point(1, 1));
+        assert_eq!(Strategy::Random.pick(&player), None);
+        Ok(())
+    }
+
+    fn player_with_cruiser() -> Result<Player<Active>> {
+        let mut new_player = Player::with_rules(
+            "Alice",
+            5,
+            &Rules {
+                fleet: vec![Ship::Cruiser],
+                ..Rules::for_grid(5)
+            },
+        );
+        new_player.place_ship(Ship::Cruiser, Point(1, 2), Direction::Horizontal)?;
+        new_player.ready()
+    }
+
+    #[test]
+    fn hunt_target_fires_next_to_hit() -> Result<()> {
+        for strategy in [Strategy::HuntTarget, Strategy::Density] {
+            let mut player = player_with_cruiser()?;
+            player.fire_at(Point(2, 2));
+
+            let point = strategy.pick(&player).unwrap();
+
+            let neighbours = [Point(1, 2), Point(3, 2), Point(2, 1), Point(2, 3)];
+            assert!(
+                neighbours.contains(&point),
+                "{} fired at {}",
+                strategy,
+                point
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn hunt_target_follows_line_of_hits() -> Result<()> {
+        for strategy in [Strategy::HuntTarget, Strategy::Density] {
+            let mut player = player_with_cruiser()?;
+            player.fire_at(Point(2, 2));
+            player.fire_at(Point(3, 2));
+
+            let point = strategy.pick(&player).unwrap();
+
+            assert!([Point(1, 2), Point(4, 2)].contains(&point));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn cheater_never_misses() -> Result<()> {
+        let mut player = player_with_cruiser()?;
+
+        for _ in 0..3 {
+            let point = Strategy::Cheater.pick(&player).unwrap();
+            assert_ne!(player.fire_at(point), Some(Fire::Miss));
+        }
+        assert!(!player.is_alive());
+        Ok(())
+    }
+
+    #[test]
+    fn strategies_sink_all_ships() -> Result<()> {
+        for strategy in [Strategy::HuntTarget, Strategy::Density, Strategy::Cheater] {
+            let mut new_player = Player::new("Alice", 10);
+            new_player.place_ships_randomly()?;
+            let mut player = new_player.ready()?;
+
+            let mut shots = 0;
+            while player.is_alive() {
+                let point = strategy.pick(&player).unwrap();
+                assert!(player.fire_at(point).is_some());
+                shots += 1;
+            }
+            assert!(shots <= 100);
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
 pub mod core;
+pub mod profile;
+pub mod server;
 pub mod sim;
 pub mod tui;
@@ -1,8 +1,16 @@
+use std::{fmt::Display, path::PathBuf, str::FromStr, time::Duration};
+
 use eyre::Result;
 
-use clap::{Parser, Subcommand};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand};
+use slagskip::core::{
+    Difficulty, OnTimeout, Resolution, Rules, Ship, ShotClock, Strategy, Variant,
+};
+use slagskip::profile;
+use slagskip::server::{self, OnDisconnect};
 use slagskip::sim;
 use slagskip::tui;
+use strum::IntoEnumIterator;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -13,18 +21,221 @@ struct Cli {
 
 #[derive(Debug, Subcommand)]
 enum Commands {
-    /// Simulate a full game to exercise game engine.
-    Sim,
+    /// Simulate games between bots to exercise the game engine.
+    Sim {
+        /// Number of games to play.
+        #[arg(long, default_value_t = 1)]
+        games: usize,
+
+        /// Number of threads to play the games on.
+        #[arg(long, default_value_t = 1)]
+        threads: usize,
+
+        /// Only print the summary, not every shot of every game.
+        #[arg(long)]
+        quiet: bool,
+
+        /// Seed of the first game, to play the same games again. Random by default.
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Print prose and grids as "text", or one JSON object per event as "json".
+        #[arg(long, default_value_t = sim::Format::Text)]
+        format: sim::Format,
+
+        /// Size of the grid.
+        #[arg(long, default_value_t = 10)]
+        size: usize,
+
+        /// Number of players.
+        #[arg(long, default_value_t = 2)]
+        players: usize,
+
+        /// Names of the players, in order, separated by commas. Alice, Bob, Cecil, and so on by default.
+        #[arg(long, value_delimiter = ',')]
+        names: Vec<String>,
+
+        /// Strategies of the players, in order, separated by commas. The last one is used for the rest.
+        #[arg(long, value_delimiter = ',', default_value = "random", value_parser = one_of::<Strategy>)]
+        strategies: Vec<Strategy>,
+
+        /// Ships of the fleet, separated by commas. All ships that fit on the grid by default.
+        #[arg(long, value_delimiter = ',', value_parser = one_of::<Ship>)]
+        fleet: Vec<Ship>,
+
+        /// Whether ships may touch: "classic" or "no-touch".
+        #[arg(long, default_value_t = Variant::Classic, value_parser = one_of::<Variant>)]
+        variant: Variant,
+
+        /// How shots resolve: "sequential", or "simultaneous" where the last players may draw.
+        #[arg(long, default_value_t = Resolution::Sequential, value_parser = one_of::<Resolution>)]
+        resolution: Resolution,
+
+        /// Number of rounds after which a game is called a draw. The number of cells on all grids by default.
+        #[arg(long)]
+        max_rounds: Option<usize>,
+    },
 
     /// Play game in terminal UI.
-    Tui,
+    Tui {
+        /// Set up the menu to watch two bots play against each other instead of playing.
+        #[arg(long)]
+        spectate: bool,
+
+        /// Number of turns the spectator view lags behind the game.
+        #[arg(long, default_value_t = 0, requires = "spectate")]
+        delay: usize,
+
+        /// How well the bots play.
+        #[arg(long, default_value_t = Difficulty::Easy)]
+        difficulty: Difficulty,
+
+        /// Seconds to fire before the turn runs out.
+        #[arg(long)]
+        shot_clock: Option<u64>,
+
+        /// What happens when the turn runs out.
+        #[arg(long, default_value_t = OnTimeout::RandomShot, requires = "shot_clock")]
+        on_timeout: OnTimeout,
+
+        /// Config file with key bindings and theme, instead of ~/.config/slagskip/config.toml.
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+
+    /// Host networked games for multiple clients.
+    Server {
+        /// Address to listen on.
+        #[arg(long, default_value = "127.0.0.1:4000")]
+        addr: String,
+
+        /// Strategy of the bot that lone players are matched against.
+        #[arg(long, default_value_t = Strategy::Random)]
+        bot: Strategy,
+
+        /// Seconds to hold the seat of a disconnected player.
+        #[arg(long, default_value_t = 60)]
+        grace: u64,
+
+        /// What happens if a disconnected player does not return in time.
+        #[arg(long, default_value_t = OnDisconnect::Forfeit)]
+        on_disconnect: OnDisconnect,
+    },
+
+    /// Show wins and losses against the bots.
+    Stats {
+        /// Only show the stats of this player.
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+/// Parses one of the values of an enum, listing them all if it is none of them.
+fn one_of<T: FromStr + IntoEnumIterator + Display>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| {
+        let values = T::iter()
+            .map(|v| v.to_string().to_lowercase())
+            .collect::<Vec<_>>();
+        format!("expected one of {}", values.join(", "))
+    })
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match &cli.command {
-        Commands::Sim => sim::main(),
-        Commands::Tui => tui::main(),
+        Commands::Sim {
+            games,
+            threads,
+            quiet,
+            seed,
+            format,
+            size,
+            players,
+            names,
+            strategies,
+            fleet,
+            variant,
+            resolution,
+            max_rounds,
+        } => {
+            let invalid = |message: String| -> ! {
+                let mut cli = Cli::command();
+                cli.build();
+                cli.find_subcommand_mut("sim")
+                    .unwrap()
+                    .error(ErrorKind::ValueValidation, message)
+                    .exit()
+            };
+            if *players < 2 {
+                invalid("A game needs at least two players.".into());
+            }
+            if names.len() > *players {
+                invalid(format!(
+                    "Got {} names for {} players.",
+                    names.len(),
+                    players
+                ));
+            }
+            if strategies.len() > *players {
+                invalid(format!(
+                    "Got {} strategies for {} players.",
+                    strategies.len(),
+                    players
+                ));
+            }
+            let bots = sim::fill_names(names, *players)
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| sim::Bot {
+                    name,
+                    strategy: strategies[i.min(strategies.len() - 1)],
+                })
+                .collect();
+            let rules = Rules {
+                fleet: match fleet.is_empty() {
+                    true => Ship::for_grid(*size),
+                    false => fleet.clone(),
+                },
+                variant: *variant,
+                shot_clock: None,
+                resolution: *resolution,
+            };
+            let mut config = sim::Config::new()
+                .rules(*size, rules)
+                .bots(bots)
+                .max_rounds(*max_rounds);
+            if let Some(seed) = seed {
+                config = config.seed(*seed);
+            }
+            if let Err(err) = config.validate() {
+                invalid(err.to_string());
+            }
+            sim::main(&config, *games, *threads, *quiet, *format)
+        }
+        Commands::Tui {
+            spectate,
+            delay,
+            difficulty,
+            shot_clock,
+            on_timeout,
+            config,
+        } => tui::main(
+            *spectate,
+            *delay,
+            *difficulty,
+            shot_clock.map(|seconds| ShotClock {
+                limit: Duration::from_secs(seconds),
+                on_timeout: *on_timeout,
+            }),
+            tui::Config::load(config.as_deref())?,
+        ),
+        Commands::Server {
+            addr,
+            bot,
+            grace,
+            on_disconnect,
+        } => server::main(addr, *bot, Duration::from_secs(*grace), *on_disconnect),
+        Commands::Stats { name } => profile::main(name.as_deref()),
     }
 }
@@ -0,0 +1,240 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use eyre::{eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::core::Difficulty;
+
+/// The players' records against the bots, kept between games.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Profiles(BTreeMap<String, Profile>);
+
+impl Profiles {
+    /// The file in the user's data directory, like `~/.local/share/slagskip/profiles.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("slagskip").join("profiles.toml"))
+    }
+
+    /// Loads the profiles from the given path, which is empty if there is no file yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(err).wrap_err_with(|| format!("Failed to read {}", path.display()))
+            }
+        };
+        toml::from_str(&text).wrap_err_with(|| format!("Invalid profiles in {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)
+            .wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.0.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Profile)> {
+        self.0.iter()
+    }
+
+    /// Adds a finished game against a bot to the player's profile.
+    pub fn record(&mut self, name: &str, difficulty: Difficulty, outcome: Outcome, shots: usize) {
+        let profile = self.0.entry(name.to_string()).or_default();
+        profile
+            .0
+            .entry(difficulty)
+            .or_default()
+            .record(outcome, shots);
+    }
+}
+
+/// A player's records, one per difficulty of the bots played against.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Profile(BTreeMap<Difficulty, Record>);
+
+impl Profile {
+    pub fn records(&self) -> impl Iterator<Item = (Difficulty, &Record)> {
+        self.0
+            .iter()
+            .map(|(difficulty, record)| (*difficulty, record))
+    }
+
+    /// All the records added together.
+    pub fn total(&self) -> Record {
+        self.0
+            .values()
+            .fold(Record::default(), |total, record| Record {
+                wins: total.wins + record.wins,
+                losses: total.losses + record.losses,
+                draws: total.draws + record.draws,
+                shots_to_win: total.shots_to_win + record.shots_to_win,
+                best_game: match (total.best_game, record.best_game) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                },
+            })
+    }
+}
+
+/// How a game ended for the player.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Record {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+
+    /// The shots fired in all the games won.
+    pub shots_to_win: usize,
+
+    /// The fewest shots fired to win a game.
+    pub best_game: Option<usize>,
+}
+
+impl Record {
+    fn record(&mut self, outcome: Outcome, shots: usize) {
+        match outcome {
+            Outcome::Win => {
+                self.wins += 1;
+                self.shots_to_win += shots;
+                self.best_game = Some(self.best_game.map_or(shots, |best| best.min(shots)));
+            }
+            Outcome::Loss => self.losses += 1,
+            Outcome::Draw => self.draws += 1,
+        }
+    }
+
+    pub fn games(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+
+    /// The share of games won, from 0 to 1.
+    pub fn win_rate(&self) -> f64 {
+        match self.games() {
+            0 => 0.0,
+            games => self.wins as f64 / games as f64,
+        }
+    }
+
+    pub fn average_shots_to_win(&self) -> Option<f64> {
+        match self.wins {
+            0 => None,
+            wins => Some(self.shots_to_win as f64 / wins as f64),
+        }
+    }
+}
+
+/// Prints the records of all players, or only the named one.
+pub fn main(name: Option<&str>) -> Result<()> {
+    let path = Profiles::default_path().ok_or_else(|| eyre!("Found no data directory"))?;
+    let profiles = Profiles::load(&path)?;
+    let profiles = profiles
+        .iter()
+        .filter(|(n, _)| name.is_none_or(|name| *n == name))
+        .collect::<Vec<_>>();
+    if profiles.is_empty() {
+        match name {
+            Some(name) => println!("{} has not played any games yet.", name),
+            None => println!("No games have been played yet."),
+        }
+        return Ok(());
+    }
+
+    for (name, profile) in profiles {
+        println!(">>> {}", name);
+        println!();
+        println!(
+            "{:<12}{:>6}{:>6}{:>8}{:>7}{:>7}{:>11}{:>6}",
+            "Difficulty", "Games", "Wins", "Losses", "Draws", "Won", "Avg shots", "Best"
+        );
+        let total = profile.total();
+        let rows = profile
+            .records()
+            .map(|(difficulty, record)| (difficulty.to_string(), record))
+            .chain([("total".to_string(), &total)]);
+        for (label, record) in rows {
+            println!(
+                "{:<12}{:>6}{:>6}{:>8}{:>7}{:>6.0}%{:>11}{:>6}",
+                label,
+                record.games(),
+                record.wins,
+                record.losses,
+                record.draws,
+                record.win_rate() * 100.0,
+                record
+                    .average_shots_to_win()
+                    .map_or("-".into(), |shots| format!("{:.1}", shots)),
+                record
+                    .best_game
+                    .map_or("-".into(), |shots| shots.to_string()),
+            );
+        }
+        println!();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_games() {
+        let mut profiles = Profiles::default();
+        profiles.record("Sam", Difficulty::Easy, Outcome::Win, 60);
+        profiles.record("Sam", Difficulty::Easy, Outcome::Win, 40);
+        profiles.record("Sam", Difficulty::Hard, Outcome::Loss, 50);
+        profiles.record("Sam", Difficulty::Hard, Outcome::Win, 45);
+
+        let profile = profiles.get("Sam").unwrap();
+        let easy = profile.records().next().unwrap().1;
+        assert_eq!(easy.games(), 2);
+        assert_eq!(easy.average_shots_to_win(), Some(50.0));
+        assert_eq!(easy.best_game, Some(40));
+
+        let total = profile.total();
+        assert_eq!(total.games(), 4);
+        assert_eq!(total.wins, 3);
+        assert_eq!(total.losses, 1);
+        assert_eq!(total.win_rate(), 0.75);
+        assert_eq!(total.best_game, Some(40));
+        assert!(profiles.get("Alex").is_none());
+    }
+
+    #[test]
+    fn save_and_load() -> Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("slagskip-test-{}", std::process::id()))
+            .join("profiles.toml");
+        assert_eq!(Profiles::load(&path)?, Profiles::default());
+
+        let mut profiles = Profiles::default();
+        profiles.record("Sam", Difficulty::Medium, Outcome::Draw, 30);
+        profiles.record("Sam Q", Difficulty::Cheater, Outcome::Win, 17);
+        profiles.save(&path)?;
+
+        let loaded = Profiles::load(&path);
+        fs::remove_dir_all(path.parent().unwrap())?;
+        assert_eq!(loaded?, profiles);
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+mod connection;
+mod lobby;
+mod protocol;
+
+use std::{
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use eyre::Result;
+
+use crate::core::Strategy;
+
+use self::lobby::Lobby;
+pub use self::lobby::OnDisconnect;
+
+pub fn main(addr: &str, bot: Strategy, grace: Duration, on_disconnect: OnDisconnect) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    println!("Listening on {}", listener.local_addr()?);
+
+    let lobby = Arc::new(Mutex::new(Lobby::new(bot, grace, on_disconnect)));
+
+    let expiring_lobby = Arc::clone(&lobby);
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs(1));
+        if let Ok(mut lobby) = expiring_lobby.lock() {
+            lobby.expire(Instant::now());
+        }
+    });
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("Failed to accept connection: {}", err);
+                continue;
+            }
+        };
+        let lobby = Arc::clone(&lobby);
+        thread::spawn(move || {
+            let peer = stream.peer_addr();
+            if let Err(err) = connection::handle(stream, lobby) {
+                eprintln!("Connection {:?} failed: {}", peer, err);
+            }
+        });
+    }
+    Ok(())
+}
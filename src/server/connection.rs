@@ -0,0 +1,131 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpStream,
+    sync::{
+        mpsc::{channel, Sender},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use eyre::{eyre, Result};
+
+use super::{
+    lobby::{GameId, Lobby},
+    protocol::{Command, Message},
+};
+
+pub fn handle(stream: TcpStream, lobby: Arc<Mutex<Lobby>>) -> Result<()> {
+    let (outbox, inbox) = channel::<Message>();
+    let mut writer = stream.try_clone()?;
+    thread::spawn(move || {
+        for message in inbox {
+            if writeln!(writer, "{}", message).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut connection = Connection {
+        lobby,
+        outbox,
+        seat: None,
+    };
+    let result = connection.serve(stream);
+    connection.leave();
+    result
+}
+
+struct Connection {
+    lobby: Arc<Mutex<Lobby>>,
+    outbox: Sender<Message>,
+    seat: Option<(GameId, usize)>,
+}
+
+impl Connection {
+    fn serve(&mut self, stream: TcpStream) -> Result<()> {
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let command = match line.parse::<Command>() {
+                Ok(Command::Quit) => break,
+                Ok(command) => command,
+                Err(err) => {
+                    self.send(Message::Error(err.to_string()));
+                    continue;
+                }
+            };
+            if let Err(err) = self.execute(command) {
+                self.send(Message::Error(err.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, command: Command) -> Result<()> {
+        let mut lobby = self
+            .lobby
+            .lock()
+            .map_err(|_| eyre!("Lobby is unavailable"))?;
+        match command {
+            Command::List => {
+                for game in lobby.list() {
+                    self.send(game);
+                }
+                self.send(Message::Ok);
+            }
+            Command::Create {
+                name,
+                grid_size,
+                rules,
+            } => {
+                self.ensure_unseated()?;
+                self.seat = Some(lobby.host(&name, grid_size, rules, self.outbox.clone())?);
+            }
+            Command::Join { id, name } => {
+                self.ensure_unseated()?;
+                let seat = lobby.join(id, &name, self.outbox.clone())?;
+                self.seat = Some((id, seat));
+            }
+            Command::Spectate { id, delay } => {
+                self.ensure_unseated()?;
+                lobby.spectate(id, delay, self.outbox.clone())?;
+                self.send(Message::Spectating { id });
+            }
+            Command::Match { name } => {
+                self.ensure_unseated()?;
+                self.seat = Some(lobby.auto_match(&name, self.outbox.clone())?);
+            }
+            Command::Fire { point } => {
+                let (id, seat) = self.seat.ok_or_else(|| eyre!("Join a game first"))?;
+                lobby.fire(id, seat, point)?;
+            }
+            Command::Resume { token } => {
+                self.ensure_unseated()?;
+                self.seat = Some(lobby.resume(&token, self.outbox.clone())?);
+            }
+            Command::Quit => {}
+        }
+        Ok(())
+    }
+
+    fn ensure_unseated(&self) -> Result<()> {
+        match self.seat {
+            Some((id, _)) => Err(eyre!("Already playing game {}", id)),
+            None => Ok(()),
+        }
+    }
+
+    fn send(&self, message: Message) {
+        // The writer thread only stops if the client is gone.
+        let _ = self.outbox.send(message);
+    }
+
+    fn leave(&mut self) {
+        if let (Some((id, seat)), Ok(mut lobby)) = (self.seat.take(), self.lobby.lock()) {
+            lobby.leave(id, seat);
+        }
+    }
+}
@@ -0,0 +1,995 @@
+use std::{
+    collections::BTreeMap,
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
+
+use eyre::{eyre, Result};
+use rand::{thread_rng, Rng};
+
+use crate::core::{Active, Fire, Game, GameResult, New, Point, Rules, Shot, Strategy, Timeout};
+
+use super::protocol::Message;
+
+pub type GameId = u32;
+
+const SEATS: usize = 2;
+const BOT_NAME: &str = "Bot";
+const DEFAULT_GRID_SIZE: usize = 10;
+
+/// What happens to the seat of a player that does not reconnect in time.
+#[derive(
+    Copy, Clone, Debug, Default, Eq, PartialEq, strum_macros::EnumString, strum_macros::Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum OnDisconnect {
+    /// The player forfeits the game.
+    #[default]
+    Forfeit,
+
+    /// A bot takes over the seat and plays the rest of the game.
+    Bot,
+}
+
+#[derive(Debug)]
+pub struct Lobby {
+    bot: Strategy,
+    grace: Duration,
+    on_disconnect: OnDisconnect,
+    next_id: GameId,
+    tables: BTreeMap<GameId, Table>,
+}
+
+impl Lobby {
+    pub fn new(bot: Strategy, grace: Duration, on_disconnect: OnDisconnect) -> Self {
+        Self {
+            bot,
+            grace,
+            on_disconnect,
+            next_id: 1,
+            tables: BTreeMap::new(),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Message> {
+        self.tables.values().map(|t| t.summary()).collect()
+    }
+
+    pub fn create(&mut self, grid_size: usize, rules: Rules) -> Result<GameId> {
+        let game = Game::with_rules(grid_size, rules)?;
+        let id = self.next_id;
+        self.next_id += 1;
+        self.tables.insert(id, Table::new(id, game));
+        Ok(id)
+    }
+
+    /// Creates a new game and seats its host in it.
+    pub fn host(
+        &mut self,
+        name: &str,
+        grid_size: usize,
+        rules: Rules,
+        outbox: Sender<Message>,
+    ) -> Result<(GameId, usize)> {
+        let id = self.create(grid_size, rules)?;
+        match self.join(id, name, outbox) {
+            Ok(seat) => Ok((id, seat)),
+            Err(err) => {
+                self.tables.remove(&id);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn join(&mut self, id: GameId, name: &str, outbox: Sender<Message>) -> Result<usize> {
+        if name == BOT_NAME {
+            return Err(eyre!("The name {} is reserved", BOT_NAME));
+        }
+        let token = format!("{:016x}", thread_rng().gen::<u64>());
+        let seat = self.table_mut(id)?.sit(Seat::Human {
+            name: name.to_string(),
+            token: token.clone(),
+            outbox: Some(outbox.clone()),
+            left_at: None,
+        })?;
+        let _ = outbox.send(Message::Session { token });
+        Ok(seat)
+    }
+
+    /// Returns a disconnected player to their seat, identified by the
+    /// session token they got when joining.
+    pub fn resume(&mut self, token: &str, outbox: Sender<Message>) -> Result<(GameId, usize)> {
+        for table in self.tables.values_mut() {
+            if let Some(seat) = table.seats.iter().position(|s| s.has_token(token)) {
+                table.resume(seat, outbox)?;
+                return Ok((table.id, seat));
+            }
+        }
+        Err(eyre!("No seat is held for this session"))
+    }
+
+    pub fn spectate(&mut self, id: GameId, delay: usize, outbox: Sender<Message>) -> Result<()> {
+        self.table_mut(id)?.watch(outbox, delay);
+        Ok(())
+    }
+
+    /// Seats the player at the oldest open game without a bot, or starts a
+    /// new game against a bot if no one else is waiting.
+    pub fn auto_match(&mut self, name: &str, outbox: Sender<Message>) -> Result<(GameId, usize)> {
+        let waiting = self
+            .tables
+            .values()
+            .find(|t| t.is_open() && !t.seats.iter().any(|s| s.is_bot()))
+            .map(|t| t.id);
+        if let Some(id) = waiting {
+            return Ok((id, self.join(id, name, outbox)?));
+        }
+
+        let id = self.create(DEFAULT_GRID_SIZE, Rules::for_grid(DEFAULT_GRID_SIZE))?;
+        let seat = self.join(id, name, outbox)?;
+        let bot = Seat::Bot {
+            name: BOT_NAME.to_string(),
+            strategy: self.bot,
+        };
+        self.table_mut(id)?.sit(bot)?;
+        Ok((id, seat))
+    }
+
+    pub fn fire(&mut self, id: GameId, seat: usize, point: Point) -> Result<Fire> {
+        self.table_mut(id)?.fire(seat, point)
+    }
+
+    /// Keeps the seat of a player that left an active game for the grace
+    /// period, so that they may resume the game.
+    pub fn leave(&mut self, id: GameId, seat: usize) {
+        if let Some(table) = self.tables.get_mut(&id) {
+            table.leave(seat, Instant::now());
+        }
+        self.tables.retain(|_, t| !t.is_abandoned());
+    }
+
+    /// Enforces shot clocks and gives up on players that have been gone for
+    /// longer than the grace period.
+    pub fn expire(&mut self, now: Instant) {
+        for table in self.tables.values_mut() {
+            table.enforce_shot_clock(now);
+            for seat in 0..table.seats.len() {
+                if table.seats[seat]
+                    .left_at()
+                    .is_some_and(|t| now.saturating_duration_since(t) >= self.grace)
+                {
+                    table.expire(seat, self.on_disconnect, self.bot);
+                }
+            }
+        }
+        self.tables.retain(|_, t| !t.is_abandoned());
+    }
+
+    fn table_mut(&mut self, id: GameId) -> Result<&mut Table> {
+        self.tables
+            .get_mut(&id)
+            .ok_or_else(|| eyre!("No game with ID {}", id))
+    }
+}
+
+#[derive(Debug)]
+enum Seat {
+    Human {
+        name: String,
+        token: String,
+        outbox: Option<Sender<Message>>,
+        left_at: Option<Instant>,
+    },
+    Bot {
+        name: String,
+        strategy: Strategy,
+    },
+}
+
+impl Seat {
+    fn name(&self) -> &str {
+        match self {
+            Self::Human { name, .. } => name,
+            Self::Bot { name, .. } => name,
+        }
+    }
+
+    fn is_bot(&self) -> bool {
+        matches!(self, Self::Bot { .. })
+    }
+
+    fn has_token(&self, token: &str) -> bool {
+        matches!(self, Self::Human { token: t, .. } if t == token)
+    }
+
+    fn left_at(&self) -> Option<Instant> {
+        match self {
+            Self::Human { left_at, .. } => *left_at,
+            Self::Bot { .. } => None,
+        }
+    }
+
+    /// Whether a human is playing in or may return to this seat.
+    fn is_held(&self) -> bool {
+        matches!(
+            self,
+            Self::Human {
+                outbox: Some(_),
+                ..
+            }
+        ) || self.left_at().is_some()
+    }
+
+    fn send(&self, message: Message) {
+        if let Self::Human {
+            outbox: Some(outbox),
+            ..
+        } = self
+        {
+            // The connection is cleaned up by its own thread if it is gone.
+            let _ = outbox.send(message);
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Stage {
+    Open(Game<New>),
+    Active(Game<Active>),
+}
+
+#[derive(Debug)]
+struct Spectator {
+    outbox: Sender<Message>,
+    delay: usize,
+    relayed: usize,
+}
+
+impl Spectator {
+    fn send(&self, message: Message) {
+        let _ = self.outbox.send(message);
+    }
+}
+
+#[derive(Debug)]
+struct Table {
+    id: GameId,
+    stage: Stage,
+    seats: Vec<Seat>,
+    spectators: Vec<Spectator>,
+    log: Vec<Shot>,
+    turn: usize,
+}
+
+impl Table {
+    fn new(id: GameId, game: Game<New>) -> Self {
+        Self {
+            id,
+            stage: Stage::Open(game),
+            seats: vec![],
+            spectators: vec![],
+            log: vec![],
+            turn: 0,
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        matches!(self.stage, Stage::Open(_)) && self.seats.len() < SEATS
+    }
+
+    fn is_finished(&self) -> bool {
+        matches!(&self.stage, Stage::Active(game) if game.result().is_some())
+    }
+
+    fn is_abandoned(&self) -> bool {
+        !self.seats.iter().any(|s| s.is_held())
+    }
+
+    fn summary(&self) -> Message {
+        let (grid_size, rules, status) = match &self.stage {
+            Stage::Open(game) => (game.grid_size, &game.rules, "open"),
+            Stage::Active(game) if game.result().is_some() => {
+                (game.grid_size, &game.rules, "finished")
+            }
+            Stage::Active(game) => (game.grid_size, &game.rules, "active"),
+        };
+        Message::Game {
+            id: self.id,
+            grid_size,
+            rules: rules.clone(),
+            seated: self.seats.len(),
+            seats: SEATS,
+            status,
+        }
+    }
+
+    fn broadcast(&self, message: Message) {
+        for spectator in self.spectators.iter() {
+            spectator.send(message.clone());
+        }
+        self.notify_seats(message);
+    }
+
+    fn notify_seats(&self, message: Message) {
+        for seat in self.seats.iter() {
+            seat.send(message.clone());
+        }
+    }
+
+    fn sit(&mut self, seat: Seat) -> Result<usize> {
+        let Stage::Open(game) = &mut self.stage else {
+            return Err(eyre!("Game {} has already started", self.id));
+        };
+        if self.seats.len() >= SEATS {
+            return Err(eyre!("Game {} is full", self.id));
+        }
+        if self.seats.iter().any(|s| s.name() == seat.name()) {
+            return Err(eyre!("The name {} is already taken", seat.name()));
+        }
+
+        if let Err(err) = game.add_player(seat.name()).place_ships_randomly() {
+            // Leave no half-placed player behind in the game
+            game.players.pop();
+            return Err(err);
+        }
+        self.seats.push(seat);
+        self.broadcast(Message::Joined {
+            id: self.id,
+            seat: self.seats.len() - 1,
+            name: self.seats[self.seats.len() - 1].name().to_string(),
+        });
+
+        if self.seats.len() == SEATS {
+            self.start()?;
+        }
+        Ok(self.seats.len() - 1)
+    }
+
+    /// Adds a spectator that sees all ships, but only learns about each
+    /// shot once `delay` more shots have been fired or the game has ended.
+    fn watch(&mut self, outbox: Sender<Message>, delay: usize) {
+        let spectator = Spectator {
+            outbox,
+            delay,
+            relayed: self.relayable(delay),
+        };
+        if let Stage::Active(game) = &self.stage {
+            let grids = game.grids_after(&self.log[..spectator.relayed]);
+            for (player, grid) in game.players.iter().zip(grids.iter()) {
+                spectator.send(Message::board(&player.name, grid, true));
+            }
+        }
+        self.spectators.push(spectator);
+    }
+
+    fn relayable(&self, delay: usize) -> usize {
+        match self.is_finished() {
+            true => self.log.len(),
+            false => self.log.len().saturating_sub(delay),
+        }
+    }
+
+    fn relay(&mut self) {
+        let mut spectators = std::mem::take(&mut self.spectators);
+        for spectator in spectators.iter_mut() {
+            let relayable = self.relayable(spectator.delay);
+            while spectator.relayed < relayable {
+                spectator.send(self.shot_message(&self.log[spectator.relayed]));
+                spectator.relayed += 1;
+            }
+        }
+        self.spectators = spectators;
+    }
+
+    fn start(&mut self) -> Result<()> {
+        let placeholder = Stage::Open(Game::new(0));
+        let Stage::Open(game) = std::mem::replace(&mut self.stage, placeholder) else {
+            unreachable!("Only open games can be started");
+        };
+        let mut game = game.start()?;
+        game.start_turn(Instant::now());
+        self.stage = Stage::Active(game);
+
+        self.broadcast(Message::Started { id: self.id });
+        for seat in 0..self.seats.len() {
+            self.send_boards(seat);
+        }
+        if let Stage::Active(game) = &self.stage {
+            for spectator in self.spectators.iter() {
+                for player in game.players.iter() {
+                    spectator.send(Message::board(&player.name, &player.grid, true));
+                }
+            }
+        }
+        self.play_bots();
+        Ok(())
+    }
+
+    /// Sends the seat's own board with ships, and the opponents' boards without.
+    fn send_boards(&self, seat: usize) {
+        if let Stage::Active(game) = &self.stage {
+            for (i, player) in game.players.iter().enumerate() {
+                self.seats[seat].send(Message::board(&player.name, &player.grid, i == seat));
+            }
+        }
+    }
+
+    fn resume(&mut self, seat: usize, outbox: Sender<Message>) -> Result<()> {
+        let Seat::Human {
+            outbox: current,
+            left_at,
+            ..
+        } = &mut self.seats[seat]
+        else {
+            return Err(eyre!("Seat {} is not held for a player", seat));
+        };
+        if current.is_some() {
+            return Err(eyre!("Seat {} is already connected", seat));
+        }
+        *current = Some(outbox);
+        *left_at = None;
+
+        self.broadcast(Message::Resumed {
+            id: self.id,
+            seat,
+            name: self.seats[seat].name().to_string(),
+        });
+        self.send_boards(seat);
+        let Stage::Active(game) = &self.stage else {
+            return Ok(());
+        };
+        match game.result() {
+            Some(GameResult::Winner(player)) => self.seats[seat].send(Message::Won {
+                name: player.name.clone(),
+            }),
+            Some(GameResult::Draw(_)) => self.seats[seat].send(Message::Draw),
+            None => self.seats[seat].send(Message::Turn {
+                name: self.seats[self.turn].name().to_string(),
+            }),
+        }
+        Ok(())
+    }
+
+    fn opponent(&self, seat: usize) -> usize {
+        (seat + 1) % self.seats.len()
+    }
+
+    fn fire(&mut self, seat: usize, point: Point) -> Result<Fire> {
+        let target = self.opponent(seat);
+        let Stage::Active(game) = &mut self.stage else {
+            return Err(eyre!("Game {} has not started yet", self.id));
+        };
+        if game.result().is_some() {
+            return Err(eyre!("Game {} has ended", self.id));
+        }
+        if self.turn != seat {
+            return Err(eyre!("It is {}'s turn", self.seats[self.turn].name()));
+        }
+
+        let shot = game
+            .fire(seat, target, point)
+            .ok_or_else(|| eyre!("Cannot fire at {}", point))?;
+        self.shot(shot);
+        self.play_bots();
+        Ok(shot.fire)
+    }
+
+    /// Lets bots take their turns until it is a human's turn or the game ends.
+    fn play_bots(&mut self) {
+        loop {
+            let target = self.opponent(self.turn);
+            let Stage::Active(game) = &mut self.stage else {
+                return;
+            };
+            if game.result().is_some() {
+                break;
+            }
+            let Seat::Bot { strategy, .. } = self.seats[self.turn] else {
+                break;
+            };
+            match game.fire_with(strategy, self.turn, target) {
+                Some(shot) => self.shot(shot),
+                None => break,
+            }
+        }
+        self.announce();
+    }
+
+    fn shot_message(&self, shot: &Shot) -> Message {
+        Message::Shot {
+            shooter: self.seats[shot.shooter].name().to_string(),
+            target: self.seats[shot.target].name().to_string(),
+            point: shot.point,
+            fire: shot.fire,
+        }
+    }
+
+    fn shot(&mut self, shot: Shot) {
+        self.notify_seats(self.shot_message(&shot));
+        self.log.push(shot);
+        self.relay();
+        self.turn = self.opponent(shot.shooter);
+        if let Stage::Active(game) = &mut self.stage {
+            game.end_turn(shot.shooter);
+            game.start_turn(Instant::now());
+        }
+    }
+
+    fn enforce_shot_clock(&mut self, now: Instant) {
+        let Stage::Active(game) = &mut self.stage else {
+            return;
+        };
+        let target = (self.turn + 1) % self.seats.len();
+        let Some(timeout) = game.enforce_shot_clock(now, self.turn, target) else {
+            return;
+        };
+
+        if timeout == Timeout::Skipped {
+            game.end_turn(self.turn);
+        }
+        let name = self.seats[self.turn].name().to_string();
+        self.broadcast(Message::TimedOut { name: name.clone() });
+        match timeout {
+            Timeout::Fired(shot) => self.shot(shot),
+            Timeout::Skipped => self.turn = target,
+            Timeout::Forfeited => self.broadcast(Message::Forfeited { name }),
+        }
+        self.play_bots();
+    }
+
+    fn announce(&mut self) {
+        let Stage::Active(game) = &self.stage else {
+            return;
+        };
+        match game.result() {
+            Some(result) => {
+                let message = match result {
+                    GameResult::Winner(player) => Message::Won {
+                        name: player.name.clone(),
+                    },
+                    GameResult::Draw(_) => Message::Draw,
+                };
+                self.relay();
+                self.broadcast(message);
+            }
+            None => self.notify_seats(Message::Turn {
+                name: self.seats[self.turn].name().to_string(),
+            }),
+        }
+    }
+
+    fn leave(&mut self, seat: usize, now: Instant) {
+        let name = self.seats[seat].name().to_string();
+        let is_finished = self.is_finished();
+        match &mut self.stage {
+            Stage::Open(game) => {
+                game.remove_player(&name);
+                self.seats.remove(seat);
+            }
+            Stage::Active(_) => {
+                if let Seat::Human {
+                    outbox, left_at, ..
+                } = &mut self.seats[seat]
+                {
+                    *outbox = None;
+                    *left_at = (!is_finished).then_some(now);
+                }
+            }
+        }
+        self.broadcast(Message::Left { name });
+    }
+
+    fn expire(&mut self, seat: usize, on_disconnect: OnDisconnect, bot: Strategy) {
+        let name = self.seats[seat].name().to_string();
+        let is_finished = self.is_finished();
+        let Stage::Active(game) = &mut self.stage else {
+            return;
+        };
+        if is_finished {
+            // Nothing is at stake anymore.
+            if let Seat::Human { left_at, .. } = &mut self.seats[seat] {
+                *left_at = None;
+            }
+            return;
+        }
+
+        match on_disconnect {
+            OnDisconnect::Forfeit => {
+                game.forfeit(seat);
+                if let Seat::Human { left_at, .. } = &mut self.seats[seat] {
+                    *left_at = None;
+                }
+                self.broadcast(Message::Forfeited { name });
+            }
+            OnDisconnect::Bot => {
+                self.seats[seat] = Seat::Bot {
+                    name: name.clone(),
+                    strategy: bot,
+                };
+                self.broadcast(Message::Replaced { name });
+            }
+        }
+        self.play_bots();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::{channel, Receiver};
+
+    use crate::core::{Grid, OnTimeout, Resolution, Ship, ShotClock, Variant};
+
+    use super::*;
+
+    const GRACE: Duration = Duration::from_secs(60);
+
+    fn drain(inbox: &Receiver<Message>) -> Vec<Message> {
+        inbox.try_iter().collect()
+    }
+
+    #[test]
+    fn create_and_list_games() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let rules = Rules {
+            fleet: vec![Ship::Destroyer],
+            variant: Variant::NoTouch,
+            shot_clock: None,
+            resolution: Resolution::Sequential,
+        };
+
+        let id = lobby.create(4, rules.clone())?;
+
+        assert_eq!(
+            lobby.list(),
+            vec![Message::Game {
+                id,
+                grid_size: 4,
+                rules,
+                seated: 0,
+                seats: 2,
+                status: "open"
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn create_with_invalid_rules() {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+
+        assert!(lobby.create(2, Rules::for_grid(10)).is_err());
+    }
+
+    #[test]
+    fn host_removes_game_if_host_cannot_join() {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, _inbox) = channel();
+
+        assert!(lobby.host("Bot", 10, Rules::for_grid(10), outbox).is_err());
+        assert!(lobby.list().is_empty());
+    }
+
+    #[test]
+    fn join_starts_game_when_full() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        let (alice_outbox, alice_inbox) = channel();
+        let (bob_outbox, _bob_inbox) = channel();
+
+        assert_eq!(lobby.join(id, "Alice", alice_outbox)?, 0);
+        assert!(lobby.join(id, "Alice", bob_outbox.clone()).is_err());
+        assert_eq!(lobby.join(id, "Bob", bob_outbox.clone())?, 1);
+        assert!(lobby.join(id, "Cecil", bob_outbox).is_err());
+
+        let messages = drain(&alice_inbox);
+        assert!(messages.contains(&Message::Started { id }));
+        assert_eq!(
+            messages.last(),
+            Some(&Message::Turn {
+                name: "Alice".into()
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn players_take_turns() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        let (outbox, _inbox) = channel();
+        lobby.join(id, "Alice", outbox.clone())?;
+        lobby.join(id, "Bob", outbox)?;
+
+        assert!(lobby.fire(id, 1, Point(0, 0)).is_err());
+        lobby.fire(id, 0, Point(0, 0))?;
+        assert!(lobby.fire(id, 0, Point(1, 0)).is_err());
+        lobby.fire(id, 1, Point(0, 0))?;
+        assert!(lobby.fire(id, 0, Point(0, 0)).is_err());
+        Ok(())
+    }
+
+    /// The points of the player's only ship.
+    fn ship_points(lobby: &Lobby, id: GameId, seat: usize) -> Vec<Point> {
+        let Stage::Active(game) = &lobby.tables[&id].stage else {
+            panic!("Game {} has not started", id);
+        };
+        game.players[seat].grid.hulls()[0].points().collect()
+    }
+
+    #[test]
+    fn sunk_player_replies_with_simultaneous_resolution() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let rules = Rules {
+            fleet: vec![Ship::Destroyer],
+            resolution: Resolution::Simultaneous,
+            ..Rules::for_grid(4)
+        };
+        let id = lobby.create(4, rules)?;
+        let (outbox, inbox) = channel();
+        lobby.join(id, "Alice", outbox.clone())?;
+        lobby.join(id, "Bob", outbox)?;
+        let [first, second] = ship_points(&lobby, id, 1)[..] else {
+            unreachable!("A destroyer has two points");
+        };
+        let alice = ship_points(&lobby, id, 0);
+        let misses = Grid::new(4)
+            .points()
+            .filter(|p| !alice.contains(p))
+            .collect::<Vec<_>>();
+
+        lobby.fire(id, 0, first)?;
+        lobby.fire(id, 1, misses[0])?;
+        lobby.fire(id, 0, second)?;
+        drain(&inbox);
+
+        // Bob is sunk, but still gets a turn in the round before Alice wins
+        lobby.fire(id, 1, misses[1])?;
+        assert!(drain(&inbox).contains(&Message::Won {
+            name: "Alice".into()
+        }));
+        assert!(lobby.fire(id, 1, Point(2, 2)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn auto_match_pairs_waiting_players() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, _inbox) = channel();
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        lobby.join(id, "Alice", outbox.clone())?;
+
+        assert_eq!(lobby.auto_match("Bob", outbox)?, (id, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn auto_match_lone_player_with_bot() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, inbox) = channel();
+
+        let (id, seat) = lobby.auto_match("Alice", outbox)?;
+
+        assert_eq!(seat, 0);
+        let messages = drain(&inbox);
+        assert!(messages.contains(&Message::Joined {
+            id,
+            seat: 1,
+            name: "Bot".into()
+        }));
+        assert!(messages.contains(&Message::Started { id }));
+
+        // The bot fires right after the player
+        lobby.fire(id, 0, Point(0, 0))?;
+        let messages = drain(&inbox);
+        assert!(matches!(
+            &messages[..],
+            [Message::Shot { shooter: a, .. }, Message::Shot { shooter: b, .. }, Message::Turn { .. }]
+                if a == "Alice" && b == "Bot"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn spectators_see_all_ships() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, _inbox) = channel();
+        let (spectator_outbox, spectator_inbox) = channel();
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        lobby.join(id, "Alice", outbox.clone())?;
+        lobby.spectate(id, 0, spectator_outbox)?;
+        lobby.join(id, "Bob", outbox)?;
+
+        let boards = drain(&spectator_inbox)
+            .into_iter()
+            .filter_map(|m| match m {
+                Message::Board { rows, .. } => Some(rows),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(boards.len(), 2);
+        assert!(boards.iter().all(|rows| rows.contains('O')));
+        Ok(())
+    }
+
+    #[test]
+    fn spectators_see_shots_after_delay() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, _inbox) = channel();
+        let (spectator_outbox, spectator_inbox) = channel();
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        lobby.join(id, "Alice", outbox.clone())?;
+        lobby.join(id, "Bob", outbox)?;
+        lobby.spectate(id, 2, spectator_outbox)?;
+        let is_shot = |m: &Message| matches!(m, Message::Shot { .. });
+
+        let fire = lobby.fire(id, 0, Point(0, 0))?;
+        lobby.fire(id, 1, Point(0, 0))?;
+        assert!(!drain(&spectator_inbox).iter().any(is_shot));
+
+        lobby.fire(id, 0, Point(1, 0))?;
+        assert_eq!(
+            drain(&spectator_inbox),
+            vec![Message::Shot {
+                shooter: "Alice".into(),
+                target: "Bob".into(),
+                point: Point(0, 0),
+                fire,
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn late_spectators_see_delayed_boards() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, _inbox) = channel();
+        let (spectator_outbox, spectator_inbox) = channel();
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        lobby.join(id, "Alice", outbox.clone())?;
+        lobby.join(id, "Bob", outbox)?;
+        lobby.fire(id, 0, Point(0, 0))?;
+        lobby.fire(id, 1, Point(0, 0))?;
+
+        lobby.spectate(id, 1, spectator_outbox)?;
+
+        let boards = drain(&spectator_inbox)
+            .into_iter()
+            .filter_map(|m| match m {
+                Message::Board { name, rows } => Some((name, rows)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // Alice's board is shown before Bob fired at it, Bob's after Alice fired at it.
+        assert!(matches!(boards[0].1.chars().next(), Some('O' | '.')));
+        assert!(matches!(boards[1].1.chars().next(), Some('X' | '_')));
+        Ok(())
+    }
+
+    #[test]
+    fn leaving_removes_abandoned_games() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, _inbox) = channel();
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        lobby.join(id, "Alice", outbox)?;
+
+        lobby.leave(id, 0);
+
+        assert!(lobby.list().is_empty());
+        Ok(())
+    }
+
+    fn session(inbox: &Receiver<Message>) -> String {
+        drain(inbox)
+            .into_iter()
+            .find_map(|m| match m {
+                Message::Session { token } => Some(token),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn resume_after_disconnect() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (alice_outbox, alice_inbox) = channel();
+        let (bob_outbox, _bob_inbox) = channel();
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        lobby.join(id, "Alice", alice_outbox)?;
+        lobby.join(id, "Bob", bob_outbox)?;
+        let token = session(&alice_inbox);
+        lobby.fire(id, 0, Point(0, 0))?;
+
+        lobby.leave(id, 0);
+        assert_eq!(lobby.list().len(), 1);
+        assert!(lobby.resume("bogus", channel().0).is_err());
+
+        let (outbox, inbox) = channel();
+        assert_eq!(lobby.resume(&token, outbox)?, (id, 0));
+        let messages = drain(&inbox);
+        assert!(matches!(&messages[0], Message::Resumed { name, .. } if name == "Alice"));
+        assert!(matches!(&messages[1], Message::Board { rows, .. } if rows.contains('O')));
+        assert!(matches!(&messages[2], Message::Board { rows, .. } if !rows.contains('O')));
+        assert_eq!(messages[3], Message::Turn { name: "Bob".into() });
+
+        // The seat is taken again
+        assert!(lobby.resume(&token, channel().0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn forfeit_after_grace_period() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, inbox) = channel();
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        lobby.join(id, "Alice", channel().0)?;
+        lobby.join(id, "Bob", outbox)?;
+        lobby.leave(id, 0);
+
+        lobby.expire(Instant::now());
+        assert!(!drain(&inbox).contains(&Message::Forfeited {
+            name: "Alice".into()
+        }));
+
+        lobby.expire(Instant::now() + GRACE);
+        let messages = drain(&inbox);
+        assert!(messages.contains(&Message::Forfeited {
+            name: "Alice".into()
+        }));
+        assert!(messages.contains(&Message::Won { name: "Bob".into() }));
+        Ok(())
+    }
+
+    #[test]
+    fn shot_clock_skips_turn() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Forfeit);
+        let (outbox, inbox) = channel();
+        let rules = Rules {
+            shot_clock: Some(ShotClock {
+                limit: Duration::from_secs(10),
+                on_timeout: OnTimeout::SkipTurn,
+            }),
+            ..Rules::for_grid(10)
+        };
+        let id = lobby.create(10, rules)?;
+        lobby.join(id, "Alice", outbox)?;
+        lobby.join(id, "Bob", channel().0)?;
+        drain(&inbox);
+
+        lobby.expire(Instant::now() + Duration::from_secs(10));
+
+        assert_eq!(
+            drain(&inbox),
+            vec![
+                Message::TimedOut {
+                    name: "Alice".into()
+                },
+                Message::Turn { name: "Bob".into() }
+            ]
+        );
+        lobby.fire(id, 1, Point(0, 0))?;
+        Ok(())
+    }
+
+    #[test]
+    fn bot_takes_over_after_grace_period() -> Result<()> {
+        let mut lobby = Lobby::new(Strategy::Random, GRACE, OnDisconnect::Bot);
+        let (outbox, inbox) = channel();
+        let id = lobby.create(10, Rules::for_grid(10))?;
+        lobby.join(id, "Alice", channel().0)?;
+        lobby.join(id, "Bob", outbox)?;
+        lobby.leave(id, 0);
+
+        lobby.expire(Instant::now() + GRACE);
+
+        let messages = drain(&inbox);
+        assert!(messages.contains(&Message::Replaced {
+            name: "Alice".into()
+        }));
+        // The bot fires in Alice's place, and it is Bob's turn
+        assert!(
+            matches!(&messages[..], [.., Message::Shot { shooter, .. }, Message::Turn { name }]
+            if shooter == "Alice" && name == "Bob")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,388 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use eyre::{eyre, Report, Result};
+
+use crate::core::{Fire, Grid, OnTimeout, Point, Resolution, Rules, Ship, ShotClock, Variant};
+
+use super::lobby::GameId;
+
+/// A request sent by a client as a single line of text.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    List,
+    Create {
+        name: String,
+        grid_size: usize,
+        rules: Rules,
+    },
+    Join {
+        id: GameId,
+        name: String,
+    },
+    Spectate {
+        id: GameId,
+        delay: usize,
+    },
+    Match {
+        name: String,
+    },
+    Fire {
+        point: Point,
+    },
+    Resume {
+        token: String,
+    },
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = Report;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default().to_uppercase();
+        let args = words.collect::<Vec<_>>();
+
+        match (command.as_str(), &args[..]) {
+            ("LIST", []) => Ok(Self::List),
+            ("CREATE", [name, options @ ..]) => {
+                let mut grid_size = 10;
+                let mut variant = Variant::default();
+                let mut fleet = None;
+                let mut clock = None;
+                let mut on_timeout = OnTimeout::default();
+                let mut resolution = Resolution::default();
+                for option in options {
+                    match option.split_once('=') {
+                        Some(("size", value)) => grid_size = value.parse()?,
+                        Some(("variant", value)) => variant = value.parse()?,
+                        Some(("clock", value)) => clock = Some(value.parse()?),
+                        Some(("on-timeout", value)) => on_timeout = value.parse()?,
+                        Some(("resolution", value)) => resolution = value.parse()?,
+                        Some(("fleet", value)) => {
+                            fleet = Some(
+                                value
+                                    .split(',')
+                                    .map(|s| s.parse::<Ship>())
+                                    .collect::<Result<Vec<_>, _>>()?,
+                            )
+                        }
+                        _ => return Err(eyre!("Unknown option {}", option)),
+                    }
+                }
+                let shot_clock = clock.map(|seconds| ShotClock {
+                    limit: Duration::from_secs(seconds),
+                    on_timeout,
+                });
+                Ok(Self::Create {
+                    name: name.to_string(),
+                    grid_size,
+                    rules: Rules {
+                        fleet: fleet.unwrap_or_else(|| Ship::for_grid(grid_size)),
+                        variant,
+                        shot_clock,
+                        resolution,
+                    },
+                })
+            }
+            ("JOIN", [id, name]) => Ok(Self::Join {
+                id: id.parse()?,
+                name: name.to_string(),
+            }),
+            ("SPECTATE", [id, options @ ..]) => {
+                let mut delay = 0;
+                for option in options {
+                    match option.split_once('=') {
+                        Some(("delay", value)) => delay = value.parse()?,
+                        _ => return Err(eyre!("Unknown option {}", option)),
+                    }
+                }
+                Ok(Self::Spectate {
+                    id: id.parse()?,
+                    delay,
+                })
+            }
+            ("MATCH", [name]) => Ok(Self::Match {
+                name: name.to_string(),
+            }),
+            ("FIRE", [point]) => Ok(Self::Fire {
+                point: point.parse()?,
+            }),
+            ("RESUME", [token]) => Ok(Self::Resume {
+                token: token.to_string(),
+            }),
+            ("QUIT", []) => Ok(Self::Quit),
+            _ => Err(eyre!("Invalid command: {}", line.trim())),
+        }
+    }
+}
+
+/// A reply or event sent to a client as a single line of text.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Message {
+    Ok,
+    Error(String),
+    Game {
+        id: GameId,
+        grid_size: usize,
+        rules: Rules,
+        seated: usize,
+        seats: usize,
+        status: &'static str,
+    },
+    Joined {
+        id: GameId,
+        seat: usize,
+        name: String,
+    },
+    Session {
+        token: String,
+    },
+    Resumed {
+        id: GameId,
+        seat: usize,
+        name: String,
+    },
+    Spectating {
+        id: GameId,
+    },
+    Started {
+        id: GameId,
+    },
+    Board {
+        name: String,
+        rows: String,
+    },
+    Turn {
+        name: String,
+    },
+    Shot {
+        shooter: String,
+        target: String,
+        point: Point,
+        fire: Fire,
+    },
+    Won {
+        name: String,
+    },
+    Draw,
+    Left {
+        name: String,
+    },
+    TimedOut {
+        name: String,
+    },
+    Forfeited {
+        name: String,
+    },
+    Replaced {
+        name: String,
+    },
+}
+
+impl Message {
+    /// Describes the grid row by row, only revealing ships if `with_ships` is set.
+    pub fn board(name: &str, grid: &Grid, with_ships: bool) -> Self {
+        let mut rows = grid.to_string().replace('\n', "/");
+        if !with_ships {
+            rows = rows.replace('O', ".");
+        }
+        Self::Board {
+            name: name.to_string(),
+            rows,
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "OK"),
+            Self::Error(message) => write!(f, "ERR {}", message),
+            Self::Game {
+                id,
+                grid_size,
+                rules,
+                seated,
+                seats,
+                status,
+            } => {
+                let fleet = rules
+                    .fleet
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                write!(
+                    f,
+                    "GAME {} size={} variant={} fleet={} resolution={} ",
+                    id, grid_size, rules.variant, fleet, rules.resolution
+                )?;
+                if let Some(shot_clock) = rules.shot_clock {
+                    write!(
+                        f,
+                        "clock={} on-timeout={} ",
+                        shot_clock.limit.as_secs(),
+                        shot_clock.on_timeout
+                    )?;
+                }
+                write!(f, "seats={}/{} status={}", seated, seats, status)
+            }
+            Self::Joined { id, seat, name } => write!(f, "JOINED {} {} {}", id, seat, name),
+            Self::Session { token } => write!(f, "SESSION {}", token),
+            Self::Resumed { id, seat, name } => write!(f, "RESUMED {} {} {}", id, seat, name),
+            Self::Spectating { id } => write!(f, "SPECTATING {}", id),
+            Self::Started { id } => write!(f, "STARTED {}", id),
+            Self::Board { name, rows } => write!(f, "BOARD {} {}", name, rows),
+            Self::Turn { name } => write!(f, "TURN {}", name),
+            Self::Shot {
+                shooter,
+                target,
+                point,
+                fire,
+            } => {
+                write!(f, "SHOT {} {} {} ", shooter, target, point)?;
+                match fire {
+                    Fire::Miss => write!(f, "miss"),
+                    Fire::Hit => write!(f, "hit"),
+                    Fire::Sunk(ship) => write!(f, "sunk {}", ship),
+                }
+            }
+            Self::Won { name } => write!(f, "WON {}", name),
+            Self::Draw => write!(f, "DRAW"),
+            Self::Left { name } => write!(f, "LEFT {}", name),
+            Self::TimedOut { name } => write!(f, "TIMEOUT {}", name),
+            Self::Forfeited { name } => write!(f, "FORFEITED {}", name),
+            Self::Replaced { name } => write!(f, "REPLACED {}", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands() -> Result<()> {
+        assert_eq!("LIST".parse::<Command>()?, Command::List);
+        assert_eq!("list".parse::<Command>()?, Command::List);
+        assert_eq!(
+            "JOIN 3 Alice".parse::<Command>()?,
+            Command::Join {
+                id: 3,
+                name: "Alice".into()
+            }
+        );
+        assert_eq!(
+            "FIRE C5".parse::<Command>()?,
+            Command::Fire { point: Point(2, 4) }
+        );
+        assert_eq!(
+            "SPECTATE 3 delay=4".parse::<Command>()?,
+            Command::Spectate { id: 3, delay: 4 }
+        );
+        assert!("FIRE 5C".parse::<Command>().is_err());
+        assert!("JOIN Alice".parse::<Command>().is_err());
+        assert!("DANCE".parse::<Command>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_create_with_options() -> Result<()> {
+        assert_eq!(
+            "CREATE Alice size=6 variant=no-touch fleet=cruiser,destroyer".parse::<Command>()?,
+            Command::Create {
+                name: "Alice".into(),
+                grid_size: 6,
+                rules: Rules {
+                    fleet: vec![Ship::Cruiser, Ship::Destroyer],
+                    variant: Variant::NoTouch,
+                    shot_clock: None,
+                    resolution: Resolution::Sequential,
+                }
+            }
+        );
+        assert_eq!(
+            "CREATE Alice size=4".parse::<Command>()?,
+            Command::Create {
+                name: "Alice".into(),
+                grid_size: 4,
+                rules: Rules::for_grid(4),
+            }
+        );
+        assert_eq!(
+            "CREATE Alice clock=30 on-timeout=skip-turn".parse::<Command>()?,
+            Command::Create {
+                name: "Alice".into(),
+                grid_size: 10,
+                rules: Rules {
+                    shot_clock: Some(ShotClock {
+                        limit: Duration::from_secs(30),
+                        on_timeout: OnTimeout::SkipTurn,
+                    }),
+                    ..Rules::for_grid(10)
+                },
+            }
+        );
+        assert_eq!(
+            "CREATE Alice resolution=simultaneous".parse::<Command>()?,
+            Command::Create {
+                name: "Alice".into(),
+                grid_size: 10,
+                rules: Rules {
+                    resolution: Resolution::Simultaneous,
+                    ..Rules::for_grid(10)
+                },
+            }
+        );
+        assert!("CREATE Alice resolution=eventually"
+            .parse::<Command>()
+            .is_err());
+        assert!("CREATE Alice speed=4".parse::<Command>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn format_messages() {
+        assert_eq!(
+            Message::Shot {
+                shooter: "Alice".into(),
+                target: "Bob".into(),
+                point: Point(2, 4),
+                fire: Fire::Sunk(Ship::Destroyer),
+            }
+            .to_string(),
+            "SHOT Alice Bob C5 sunk Destroyer"
+        );
+        assert_eq!(
+            Message::Error("Not your turn".into()).to_string(),
+            "ERR Not your turn"
+        );
+        assert_eq!(
+            Message::Game {
+                id: 1,
+                grid_size: 4,
+                rules: Rules {
+                    fleet: vec![Ship::Destroyer],
+                    resolution: Resolution::Simultaneous,
+                    ..Rules::for_grid(4)
+                },
+                seated: 1,
+                seats: 2,
+                status: "open",
+            }
+            .to_string(),
+            "GAME 1 size=4 variant=classic fleet=Destroyer resolution=simultaneous seats=1/2 status=open"
+        );
+    }
+
+    #[test]
+    fn board_hides_ships() {
+        let grid = Grid::new(2);
+
+        assert_eq!(
+            Message::board("Alice", &grid, false).to_string(),
+            "BOARD Alice ../.."
+        );
+    }
+}
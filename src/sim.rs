@@ -1,94 +1,963 @@
-use eyre::Result;
+use std::{
+    fmt::Write,
+    io::{self, Write as _},
+    thread,
+    time::{Duration, Instant},
+};
 
-use crate::core::{Fire, Game, GameResult, Grid};
+use eyre::{eyre, Result};
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use serde::Serialize;
 
-pub fn main() -> Result<()> {
-    let mut game = Game::new(10);
-    game.add_player("Alice");
-    game.add_player("Bob");
+use crate::core::{DrawReason, Fire, Game, GameResult, Grid, Player, Rules, Shot, Strategy};
 
-    println!("Placing ships...");
-    println!();
-    for player in game.players.iter() {
-        player.place_ships_randomly()?;
-        println!(">>> {}", player.name);
-        println!();
-        print_grid(&player.grid);
-        println!();
+/// A bot taking part in simulated games.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bot {
+    pub name: String,
+    pub strategy: Strategy,
+}
+
+impl Bot {
+    pub fn new(name: &str, strategy: Strategy) -> Self {
+        Self {
+            name: name.to_string(),
+            strategy,
+        }
+    }
+}
+
+/// The names that bots get unless they are named, by their order in the game.
+const NAMES: [&str; 8] = [
+    "Alice", "Bob", "Cecil", "Dave", "Eve", "Frank", "Grace", "Heidi",
+];
+
+pub fn default_name(player: usize) -> String {
+    match NAMES.get(player) {
+        Some(name) => name.to_string(),
+        None => format!("Player {}", player + 1),
+    }
+}
+
+/// The given names, followed by default names that are not taken for the rest.
+pub fn fill_names(given: &[String], players: usize) -> Vec<String> {
+    let mut names = given.to_vec();
+    for player in names.len()..players {
+        let name = (player..)
+            .map(default_name)
+            .find(|name| !names.contains(name))
+            .unwrap();
+        names.push(name);
+    }
+    names
+}
+
+/// What to simulate: the grid, the rules, and the bots that play.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub grid_size: usize,
+    pub rules: Rules,
+    pub bots: Vec<Bot>,
+
+    /// The most rounds a game may last before it is called a draw. See `round_limit`.
+    pub max_rounds: Option<usize>,
+
+    /// The seed of the first game. Each following game uses the next seed, so that
+    /// any game can be played again on its own.
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self {
+            grid_size: 10,
+            rules: Rules::for_grid(10),
+            bots: (0..2)
+                .map(|i| Bot::new(&default_name(i), Strategy::Random))
+                .collect(),
+            max_rounds: None,
+            seed: thread_rng().gen(),
+        }
+    }
+
+    pub fn max_rounds(mut self, max_rounds: Option<usize>) -> Self {
+        self.max_rounds = max_rounds;
+        self
     }
 
-    let game = game.start()?;
+    pub fn rules(mut self, grid_size: usize, rules: Rules) -> Self {
+        self.grid_size = grid_size;
+        self.rules = rules;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn bots(mut self, bots: Vec<Bot>) -> Self {
+        self.bots = bots;
+        self
+    }
+
+    /// The most rounds a game may last, which by default is the number of cells on all
+    /// grids, so that no game goes on forever.
+    pub fn round_limit(&self) -> usize {
+        self.max_rounds
+            .unwrap_or(self.grid_size * self.grid_size * self.bots.len())
+    }
+
+    /// Checks that games can be played with the rules and bots.
+    pub fn validate(&self) -> Result<()> {
+        if self.bots.len() < 2 {
+            return Err(eyre!("A game needs at least two players."));
+        }
+        for (i, bot) in self.bots.iter().enumerate() {
+            if bot.name.trim().is_empty() {
+                return Err(eyre!("Player {} needs a name.", i + 1));
+            }
+            if self.bots[..i].iter().any(|b| b.name == bot.name) {
+                return Err(eyre!("{} is the name of more than one player.", bot.name));
+            }
+        }
+        if self.max_rounds == Some(0) {
+            return Err(eyre!("A game needs at least one round."));
+        }
+        self.rules.validate(self.grid_size)?;
+
+        // The fleet may have enough cells, but still not find room, e.g. if ships may not touch
+        let mut player = Player::with_rules("", self.grid_size, &self.rules);
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        player.place_ships_with(&mut rng).map_err(|_| {
+            eyre!(
+                "Found no room for the fleet on a {}x{} grid with {} rules.",
+                self.grid_size,
+                self.grid_size,
+                self.rules.variant
+            )
+        })
+    }
+}
+
+/// How the sim prints the games and the summary.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+    strum_macros::Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Format {
+    /// Prose and grids, for reading.
+    #[default]
+    Text,
+
+    /// One JSON object per line for each event, for analysis scripts.
+    Json,
+}
+
+/// Something that happened in a simulated game, in the order it happened.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// A bot placed its ships.
+    Placed { player: usize, grid: Grid },
+
+    /// A shot was fired in the given round, counting from 1.
+    Shot { round: usize, shot: Shot },
+
+    /// A bot lost its last ship in the given round.
+    Eliminated { round: usize, player: usize },
+}
+
+/// How a simulated game ended.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Outcome {
+    /// The index of the winning bot in `Config::bots`, or none if the game was a draw.
+    pub winner: Option<usize>,
+    pub draw: Option<DrawReason>,
+    pub rounds: usize,
+    pub shots: usize,
+}
+
+/// A simulated game from start to end.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameLog {
+    pub seed: u64,
+    pub events: Vec<Event>,
+
+    /// The bots' grids when the game ended.
+    pub grids: Vec<Grid>,
+    pub outcome: Outcome,
+}
 
+/// Plays one game, with every random choice decided by the seed.
+///
+/// In each round, every bot still in the game fires once at each opponent, resolved as
+/// the rules say. With simultaneous resolution, several bots may go down together.
+pub fn play(config: &Config, seed: u64) -> Result<GameLog> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut events = vec![];
+
+    let mut game = Game::with_rules(config.grid_size, config.rules.clone())?;
+    for (i, bot) in config.bots.iter().enumerate() {
+        game.add_player(&bot.name).place_ships_with(&mut rng)?;
+        events.push(Event::Placed {
+            player: i,
+            grid: game.players[i].grid.clone(),
+        });
+    }
+    let mut game = game.start()?;
+
+    let (mut rounds, mut shots) = (0, 0);
     while game.result().is_none() {
-        for turn in game.round() {
-            for opponent in turn.opponents.iter() {
-                match opponent.fire_at_random() {
-                    Some((point, Fire::Miss)) => {
-                        println!(
-                            "{} fired at {} {} and missed.",
-                            turn.player.name, opponent.name, point
-                        );
-                    }
-                    Some((point, Fire::Hit)) => {
-                        println!(
-                            "{} fired at {} {} and hit!",
-                            turn.player.name, opponent.name, point
-                        );
-                    }
-                    Some((point, Fire::Sunk(ship))) => {
-                        println!(
-                            "{} fired at {} {} and sunk a {}!",
-                            turn.player.name, opponent.name, point, ship
-                        );
-                    }
-                    None => {
-                        println!("No more cells to hit!")
-                    }
-                }
+        if rounds >= config.round_limit() {
+            game.end_in_draw(DrawReason::RoundLimit);
+            break;
+        }
+        let alive = game
+            .players
+            .iter()
+            .map(|p| p.is_alive())
+            .collect::<Vec<_>>();
+        let round = game.play_round(|shooter, target| {
+            config.bots[shooter].strategy.pick_with(target, &mut rng)
+        });
+        rounds += 1;
+        for (i, shot) in round.iter().enumerate() {
+            shots += 1;
+            events.push(Event::Shot {
+                round: rounds,
+                shot: *shot,
+            });
+            let is_last_sink = |shot: &Shot| {
+                matches!(shot.fire, Fire::Sunk(_))
+                    && !round[i + 1..]
+                        .iter()
+                        .any(|s| s.target == shot.target && matches!(s.fire, Fire::Sunk(_)))
+            };
+            if is_last_sink(shot) && alive[shot.target] && !game.players[shot.target].is_alive() {
+                events.push(Event::Eliminated {
+                    round: rounds,
+                    player: shot.target,
+                });
             }
         }
-        println!();
+        if round.is_empty() {
+            // No one has anything left to fire at, so the game would never end
+            rounds -= 1;
+            game.end_in_draw(DrawReason::Stalemate);
+        }
     }
 
-    match game.result() {
-        Some(GameResult::Winner(winner)) => {
-            println!("{} won!", winner.name);
-            println!();
+    let (winner, draw) = match game.result() {
+        Some(GameResult::Winner(_)) => (game.players.iter().position(|p| p.is_alive()), None),
+        Some(GameResult::Draw(reason)) => (None, Some(reason)),
+        None => unreachable!("The game ends before the loop does"),
+    };
+    Ok(GameLog {
+        seed,
+        events,
+        grids: game.players.iter().map(|p| p.grid.clone()).collect(),
+        outcome: Outcome {
+            winner,
+            draw,
+            rounds,
+            shots,
+        },
+    })
+}
+
+/// The outcomes of many games, added up.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Summary {
+    pub games: usize,
+
+    /// The games won by each bot, in the order of `Config::bots`.
+    pub wins: Vec<usize>,
+    pub draws: usize,
+
+    /// The draws where no one could fire, which are also counted in `draws`.
+    pub stalemates: usize,
+
+    /// The draws at the round limit, which are also counted in `draws`.
+    pub round_limits: usize,
+    pub rounds: usize,
+    pub shots: usize,
+}
+
+impl Summary {
+    fn new(bots: usize) -> Self {
+        Self {
+            wins: vec![0; bots],
+            ..Self::default()
         }
-        Some(GameResult::Draw) => {
-            println!("Game ended in a tie!");
-            println!();
+    }
+
+    fn add(&mut self, outcome: &Outcome) {
+        self.games += 1;
+        match outcome.winner {
+            Some(winner) => self.wins[winner] += 1,
+            None => self.draws += 1,
+        }
+        match outcome.draw {
+            Some(DrawReason::Stalemate) => self.stalemates += 1,
+            Some(DrawReason::RoundLimit) => self.round_limits += 1,
+            Some(DrawReason::AllEliminated) | None => {}
+        }
+        self.rounds += outcome.rounds;
+        self.shots += outcome.shots;
+    }
+
+    fn merge(&mut self, other: Summary) {
+        self.games += other.games;
+        for (wins, other) in self.wins.iter_mut().zip(other.wins) {
+            *wins += other;
         }
-        None => {}
+        self.draws += other.draws;
+        self.stalemates += other.stalemates;
+        self.round_limits += other.round_limits;
+        self.rounds += other.rounds;
+        self.shots += other.shots;
+    }
+
+    /// The given count per game played.
+    fn rate(&self, count: usize) -> f64 {
+        match self.games {
+            0 => 0.0,
+            games => count as f64 / games as f64,
+        }
+    }
+
+    pub fn win_rate(&self, bot: usize) -> f64 {
+        self.rate(self.wins[bot])
     }
 
-    for player in game.players.iter() {
-        println!(">>> {}", player.name);
-        println!();
-        print_grid(&player.grid);
-        println!();
+    pub fn draw_rate(&self) -> f64 {
+        self.rate(self.draws)
     }
 
+    pub fn average_rounds(&self) -> f64 {
+        self.rate(self.rounds)
+    }
+}
+
+/// Plays independent games on the given number of threads, and adds up the outcomes.
+///
+/// Game `i` is played with seed `config.seed + i`, so the summary is the same no matter
+/// how many threads play the games.
+pub fn simulate_many(config: &Config, games: usize, threads: usize) -> Result<Summary> {
+    simulate_each(config, games, threads, |_| {})
+}
+
+/// Like `simulate_many`, but also passes each game to `report` as soon as it ends.
+pub fn simulate_each(
+    config: &Config,
+    games: usize,
+    threads: usize,
+    report: impl Fn(GameLog) + Sync,
+) -> Result<Summary> {
+    let threads = threads.clamp(1, games.max(1));
+    let report = &report;
+    thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|worker| {
+                scope.spawn(move || -> Result<Summary> {
+                    let mut summary = Summary::new(config.bots.len());
+                    for i in (worker..games).step_by(threads) {
+                        let log = play(config, config.seed.wrapping_add(i as u64))?;
+                        summary.add(&log.outcome);
+                        report(log);
+                    }
+                    Ok(summary)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut summary = Summary::new(config.bots.len());
+        for worker in workers {
+            let worker = worker
+                .join()
+                .map_err(|_| eyre!("A simulation thread panicked."))?;
+            summary.merge(worker?);
+        }
+        Ok(summary)
+    })
+}
+
+pub fn main(
+    config: &Config,
+    games: usize,
+    threads: usize,
+    quiet: bool,
+    format: Format,
+) -> Result<()> {
+    let started = Instant::now();
+    let summary = simulate_each(config, games, threads, |log| {
+        if !quiet {
+            // Printed at once, so that games played at the same time do not mix
+            emit(match format {
+                Format::Text => describe(config, &log),
+                Format::Json => json_lines(config, &log),
+            });
+        }
+    })?;
+    let elapsed = started.elapsed();
+
+    emit(match format {
+        Format::Text => describe_summary(config, &summary, elapsed),
+        Format::Json => json_line(&JsonEvent::Summary(JsonSummary::new(
+            config, &summary, elapsed,
+        ))),
+    });
     Ok(())
 }
 
-fn print_grid(grid: &Grid) {
-    // Print header
-    print!("   ");
+fn emit(text: String) {
+    // Stop quietly if the reader goes away, like `head` does
+    let _ = io::stdout().lock().write_all(text.as_bytes());
+}
+
+fn describe_summary(config: &Config, summary: &Summary, elapsed: Duration) -> String {
+    let name_width = config
+        .bots
+        .iter()
+        .map(|bot| bot.name.chars().count())
+        .chain([5])
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    writeln!(
+        out,
+        "Played {} {} with seeds from {} in {:.2?} ({:.0} games/s).",
+        summary.games,
+        if summary.games == 1 { "game" } else { "games" },
+        config.seed,
+        elapsed,
+        summary.games as f64 / elapsed.as_secs_f64()
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    for (i, bot) in config.bots.iter().enumerate() {
+        writeln!(
+            out,
+            "{:<width$}  {:>6} wins  {:>5.1}%  ({})",
+            bot.name,
+            summary.wins[i],
+            summary.win_rate(i) * 100.0,
+            bot.strategy,
+            width = name_width
+        )
+        .unwrap();
+    }
+    writeln!(
+        out,
+        "{:<width$}  {:>6}       {:>5.1}%",
+        "Draws",
+        summary.draws,
+        summary.draw_rate() * 100.0,
+        width = name_width
+    )
+    .unwrap();
+    if summary.stalemates + summary.round_limits > 0 {
+        writeln!(
+            out,
+            "{:<width$}  {} in stalemate, {} at the round limit",
+            "",
+            summary.stalemates,
+            summary.round_limits,
+            width = name_width
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "{:.1} rounds per game on average.",
+        summary.average_rounds()
+    )
+    .unwrap();
+    out
+}
+
+/// A line of JSON output. Every event of a game has the game's seed, to tell the
+/// games apart.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+enum JsonEvent<'a> {
+    Placement {
+        game: u64,
+        player: &'a str,
+        ships: Vec<JsonShip>,
+    },
+    Shot {
+        game: u64,
+        round: usize,
+        shooter: &'a str,
+        target: &'a str,
+        point: String,
+        fire: &'static str,
+    },
+    Sink {
+        game: u64,
+        round: usize,
+        shooter: &'a str,
+        target: &'a str,
+        ship: String,
+    },
+    Elimination {
+        game: u64,
+        round: usize,
+        player: &'a str,
+    },
+    Result {
+        game: u64,
+        winner: Option<&'a str>,
+        draw: Option<String>,
+        rounds: usize,
+        shots: usize,
+    },
+    Summary(JsonSummary<'a>),
+}
+
+/// A ship with the cells it covers.
+#[derive(Serialize)]
+struct JsonShip {
+    ship: String,
+    cells: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct JsonSummary<'a> {
+    games: usize,
+    first_seed: u64,
+    bots: Vec<JsonBot<'a>>,
+    draws: usize,
+    draw_rate: f64,
+    stalemates: usize,
+    round_limits: usize,
+    rounds: usize,
+    shots: usize,
+    average_rounds: f64,
+    seconds: f64,
+}
+
+impl<'a> JsonSummary<'a> {
+    fn new(config: &'a Config, summary: &Summary, elapsed: Duration) -> Self {
+        Self {
+            games: summary.games,
+            first_seed: config.seed,
+            bots: config
+                .bots
+                .iter()
+                .enumerate()
+                .map(|(i, bot)| JsonBot {
+                    name: &bot.name,
+                    strategy: bot.strategy.to_string(),
+                    wins: summary.wins[i],
+                    win_rate: summary.win_rate(i),
+                })
+                .collect(),
+            draws: summary.draws,
+            draw_rate: summary.draw_rate(),
+            stalemates: summary.stalemates,
+            round_limits: summary.round_limits,
+            rounds: summary.rounds,
+            shots: summary.shots,
+            average_rounds: summary.average_rounds(),
+            seconds: elapsed.as_secs_f64(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonBot<'a> {
+    name: &'a str,
+    strategy: String,
+    wins: usize,
+    win_rate: f64,
+}
+
+fn json_line(event: &JsonEvent) -> String {
+    serde_json::to_string(event).unwrap() + "\n"
+}
+
+fn json_lines(config: &Config, log: &GameLog) -> String {
+    let name = |player: usize| config.bots[player].name.as_str();
+    let game = log.seed;
+    let mut lines = vec![];
+    for event in &log.events {
+        match event {
+            Event::Placed { player, grid } => lines.push(JsonEvent::Placement {
+                game,
+                player: name(*player),
+                ships: grid
+                    .hulls()
+                    .iter()
+                    .map(|hull| JsonShip {
+                        ship: hull.ship.to_string().to_lowercase(),
+                        cells: hull.points().map(|p| p.to_string()).collect(),
+                    })
+                    .collect(),
+            }),
+            Event::Shot { round, shot } => {
+                let (shooter, target) = (name(shot.shooter), name(shot.target));
+                lines.push(JsonEvent::Shot {
+                    game,
+                    round: *round,
+                    shooter,
+                    target,
+                    point: shot.point.to_string(),
+                    fire: match shot.fire {
+                        Fire::Miss => "miss",
+                        Fire::Hit => "hit",
+                        Fire::Sunk(_) => "sunk",
+                    },
+                });
+                if let Fire::Sunk(ship) = shot.fire {
+                    lines.push(JsonEvent::Sink {
+                        game,
+                        round: *round,
+                        shooter,
+                        target,
+                        ship: ship.to_string().to_lowercase(),
+                    });
+                }
+            }
+            Event::Eliminated { round, player } => lines.push(JsonEvent::Elimination {
+                game,
+                round: *round,
+                player: name(*player),
+            }),
+        }
+    }
+    lines.push(JsonEvent::Result {
+        game,
+        winner: log.outcome.winner.map(name),
+        draw: log.outcome.draw.map(|reason| reason.to_string()),
+        rounds: log.outcome.rounds,
+        shots: log.outcome.shots,
+    });
+    lines.iter().map(json_line).collect()
+}
+
+/// Tells what happened in the game, with the grids before and after.
+fn describe(config: &Config, log: &GameLog) -> String {
+    let name = |player: usize| &config.bots[player].name;
+    let mut out = String::new();
+    writeln!(out, "Playing game with seed {}...", log.seed).unwrap();
+    writeln!(out).unwrap();
+
+    writeln!(out, "Placing ships...").unwrap();
+    writeln!(out).unwrap();
+    let mut round = 0;
+    for event in &log.events {
+        match event {
+            Event::Placed { player, grid } => {
+                writeln!(out, ">>> {}", name(*player)).unwrap();
+                writeln!(out).unwrap();
+                write_grid(&mut out, grid);
+                writeln!(out).unwrap();
+            }
+            Event::Shot { round: r, shot } => {
+                if *r != round && round > 0 {
+                    writeln!(out).unwrap();
+                }
+                round = *r;
+                let (shooter, target) = (name(shot.shooter), name(shot.target));
+                match shot.fire {
+                    Fire::Miss => writeln!(
+                        out,
+                        "{} fired at {} {} and missed.",
+                        shooter, target, shot.point
+                    ),
+                    Fire::Hit => writeln!(
+                        out,
+                        "{} fired at {} {} and hit!",
+                        shooter, target, shot.point
+                    ),
+                    Fire::Sunk(ship) => writeln!(
+                        out,
+                        "{} fired at {} {} and sunk a {}!",
+                        shooter, target, shot.point, ship
+                    ),
+                }
+                .unwrap();
+            }
+            Event::Eliminated { player, .. } => {
+                writeln!(out, "{} has no ships left.", name(*player)).unwrap();
+            }
+        }
+    }
+    writeln!(out).unwrap();
+
+    match log.outcome.winner {
+        Some(winner) => writeln!(out, "{} won!", name(winner)),
+        None => match log.outcome.draw {
+            Some(DrawReason::Stalemate) => {
+                writeln!(out, "Game ended in a tie, as no one could fire!")
+            }
+            Some(DrawReason::RoundLimit) => {
+                writeln!(out, "Game ended in a tie at the round limit!")
+            }
+            _ => writeln!(out, "Game ended in a tie!"),
+        },
+    }
+    .unwrap();
+    writeln!(out).unwrap();
+
+    for (player, grid) in log.grids.iter().enumerate() {
+        writeln!(out, ">>> {}", name(player)).unwrap();
+        writeln!(out).unwrap();
+        write_grid(&mut out, grid);
+        writeln!(out).unwrap();
+    }
+    out
+}
+
+fn write_grid(out: &mut String, grid: &Grid) {
+    // Header
+    write!(out, "   ").unwrap();
     for x in 0..grid.size {
-        print!("{:>2}", index_to_char(x));
+        write!(out, "{:>2}", index_to_char(x)).unwrap();
     }
-    println!();
+    writeln!(out).unwrap();
 
     for (y, row) in grid.to_string().lines().enumerate() {
-        print!("{:>2} ", y + 1);
+        write!(out, "{:>2} ", y + 1).unwrap();
         for ch in row.chars() {
-            print!(" {}", ch);
+            write!(out, " {}", ch).unwrap();
         }
-        println!();
+        writeln!(out).unwrap();
     }
 }
 
 fn index_to_char(i: usize) -> char {
     (65u8 + i as u8) as char
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_plays_same_game() -> Result<()> {
+        let config = Config::new().bots(vec![
+            Bot::new("Alice", Strategy::HuntTarget),
+            Bot::new("Bob", Strategy::Density),
+        ]);
+
+        let log = play(&config, 7)?;
+
+        assert_eq!(log, play(&config, 7)?);
+        assert_ne!(log.events, play(&config, 8)?.events);
+        let eliminated = log
+            .events
+            .iter()
+            .filter(|e| matches!(e, Event::Eliminated { .. }))
+            .count();
+        assert!(matches!(
+            (log.outcome.winner, eliminated),
+            (Some(_), 1) | (None, 2)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn more_than_two_players() -> Result<()> {
+        let bots = (0..4)
+            .map(|i| Bot::new(&default_name(i), Strategy::HuntTarget))
+            .collect();
+        let config = Config::new().bots(bots);
+
+        let log = play(&config, 7)?;
+
+        let eliminated = log
+            .events
+            .iter()
+            .filter(|e| matches!(e, Event::Eliminated { .. }))
+            .count();
+        match log.outcome.winner {
+            Some(_) => assert_eq!(eliminated, 3),
+            None => assert_eq!(eliminated, 4),
+        }
+        assert_eq!(log.grids.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn round_limit_ends_in_draw() -> Result<()> {
+        let config = Config::new().max_rounds(Some(3));
+
+        let log = play(&config, 7)?;
+
+        assert_eq!(log.outcome.winner, None);
+        assert_eq!(log.outcome.draw, Some(DrawReason::RoundLimit));
+        assert_eq!(log.outcome.rounds, 3);
+        assert_eq!(log.outcome.shots, 6);
+
+        let summary = simulate_many(&config, 4, 2)?;
+        assert_eq!((summary.draws, summary.round_limits), (4, 4));
+        Ok(())
+    }
+
+    #[test]
+    fn round_limit_by_default() {
+        assert_eq!(Config::new().round_limit(), 200);
+        assert_eq!(Config::new().rules(5, Rules::for_grid(5)).round_limit(), 50);
+        assert_eq!(Config::new().max_rounds(Some(3)).round_limit(), 3);
+    }
+
+    #[test]
+    fn resolution_decides_if_bots_can_draw() -> Result<()> {
+        use crate::core::{Resolution, Ship};
+
+        let config = |resolution| {
+            let rules = Rules {
+                fleet: vec![Ship::Destroyer],
+                resolution,
+                ..Rules::for_grid(4)
+            };
+            Config::new().rules(4, rules).bots(vec![
+                Bot::new("Alice", Strategy::Cheater),
+                Bot::new("Bob", Strategy::Cheater),
+            ])
+        };
+
+        // Both bots sink the other's destroyer in the second round
+        let log = play(&config(Resolution::Sequential), 7)?;
+        assert_eq!((log.outcome.winner, log.outcome.rounds), (Some(0), 2));
+        assert_eq!(log.outcome.shots, 3);
+
+        let log = play(&config(Resolution::Simultaneous), 7)?;
+        assert_eq!(log.outcome.winner, None);
+        assert_eq!(log.outcome.draw, Some(DrawReason::AllEliminated));
+        assert_eq!(log.outcome.shots, 4);
+        let eliminated = log
+            .events
+            .iter()
+            .filter(|e| matches!(e, Event::Eliminated { round: 2, .. }))
+            .count();
+        assert_eq!(eliminated, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn fill_names_skips_taken_names() {
+        let given = ["Bob".to_string(), "Dave".to_string()];
+
+        assert_eq!(fill_names(&given[..1], 2), ["Bob", "Cecil"]);
+        assert_eq!(fill_names(&given, 4), ["Bob", "Dave", "Cecil", "Eve"]);
+        assert_eq!(fill_names(&[], 2), ["Alice", "Bob"]);
+    }
+
+    #[test]
+    fn validate_config() {
+        use crate::core::{Ship, Variant};
+
+        assert!(Config::new().validate().is_ok());
+
+        let one_bot = Config::new().bots(vec![Bot::new("Alice", Strategy::Random)]);
+        assert!(one_bot.validate().is_err());
+
+        let same_names = Config::new().bots(vec![
+            Bot::new("Alice", Strategy::Random),
+            Bot::new("Alice", Strategy::Density),
+        ]);
+        assert!(same_names.validate().is_err());
+
+        assert!(Config::new().max_rounds(Some(0)).validate().is_err());
+
+        let too_large = Rules {
+            fleet: vec![Ship::Carrier],
+            ..Rules::for_grid(4)
+        };
+        assert!(Config::new().rules(4, too_large).validate().is_err());
+
+        let no_room = Rules {
+            variant: Variant::NoTouch,
+            ..Rules::for_grid(5)
+        };
+        let err = Config::new().rules(5, no_room).validate().unwrap_err();
+        assert!(err.to_string().contains("no-touch"), "{}", err);
+    }
+
+    #[test]
+    fn tight_fleet_is_placed_in_every_game() -> Result<()> {
+        use crate::core::{Ship, Variant};
+
+        // Most attempts to place these ships leave no room for the last ones
+        let tight = Rules {
+            fleet: vec![
+                Ship::Battleship,
+                Ship::Cruiser,
+                Ship::Submarine,
+                Ship::Destroyer,
+            ],
+            variant: Variant::NoTouch,
+            ..Rules::for_grid(5)
+        };
+        let config = Config::new().rules(5, tight).seed(3);
+
+        config.validate()?;
+        let summary = simulate_many(&config, 5, 1)?;
+        assert_eq!(summary.games, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn json_lines_per_event() -> Result<()> {
+        let config = Config::new();
+        let log = play(&config, 7)?;
+
+        let lines = json_lines(&config, &log)
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<serde_json::Value>, _>>()?;
+
+        let count = |event: &str| lines.iter().filter(|l| l["event"] == event).count();
+        assert_eq!(count("placement"), 2);
+        assert_eq!(count("shot"), log.outcome.shots);
+        let sunk = log
+            .events
+            .iter()
+            .filter(|e| matches!(e, Event::Shot { shot, .. } if matches!(shot.fire, Fire::Sunk(_))))
+            .count();
+        assert_eq!(count("sink"), sunk);
+        let eliminated = match log.outcome.winner {
+            Some(_) => 1,
+            None => 2,
+        };
+        assert_eq!(count("elimination"), eliminated);
+        assert_eq!(lines[0]["ships"][0]["cells"].as_array().unwrap().len(), 5);
+        assert!(lines.iter().all(|l| l["game"] == 7));
+        let result = lines.last().unwrap();
+        assert_eq!(result["event"], "result");
+        assert_eq!(result["rounds"], log.outcome.rounds);
+        Ok(())
+    }
+
+    #[test]
+    fn simulate_many_on_threads() -> Result<()> {
+        let config = Config::new().seed(42);
+
+        let summary = simulate_many(&config, 20, 3)?;
+
+        assert_eq!(summary, simulate_many(&config, 20, 1)?);
+        assert_eq!(summary.games, 20);
+        assert_eq!(summary.wins.iter().sum::<usize>() + summary.draws, 20);
+        assert!(summary.average_rounds() >= 17.0); // At least one shot per ship cell
+        Ok(())
+    }
+}
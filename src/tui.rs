@@ -1,23 +1,57 @@
+mod animation;
 mod app;
+mod config;
 mod cursor;
+mod heatmap;
+mod menu;
 mod terminal;
+mod theme;
 mod widgets;
 
+use std::path::PathBuf;
+
 use eyre::Result;
 
-use crate::core::Game;
+use crate::{
+    core::{Difficulty, ShotClock},
+    profile::Profiles,
+};
 
-use self::app::App;
+use self::app::Exit;
+pub use self::config::Config;
+use self::menu::{Menu, Setup};
 
-pub fn main() -> Result<()> {
-    let mut game = Game::new(10);
-    game.add_player("Player").place_ships_randomly()?;
-    game.add_player("Bot").place_ships_randomly()?;
-    let game = game.start()?;
+pub fn main(
+    spectate: bool,
+    delay: usize,
+    difficulty: Difficulty,
+    shot_clock: Option<ShotClock>,
+    config: Config,
+) -> Result<()> {
+    let setup = Setup::new(spectate, delay, difficulty, shot_clock);
 
     terminal::install_panic_hook();
     let mut terminal = terminal::init()?;
-    let app_result = App::new(game).run(&mut terminal);
+    let result = run(&mut terminal, setup, &config, Profiles::default_path());
     terminal::restore()?;
-    app_result
+    result
+}
+
+/// Sets up a game in the menu and plays it, going back to the menu to play again.
+fn run(
+    terminal: &mut terminal::Type,
+    mut setup: Setup,
+    config: &Config,
+    profiles: Option<PathBuf>,
+) -> Result<()> {
+    loop {
+        let mut menu = Menu::new(setup, config.clone()).profiles(profiles.clone());
+        let Some(mut app) = menu.run(terminal)? else {
+            return Ok(());
+        };
+        setup = menu.setup;
+        if app.run(terminal)? == Exit::Quit {
+            return Ok(());
+        }
+    }
 }
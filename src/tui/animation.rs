@@ -0,0 +1,38 @@
+use std::time::{Duration, Instant};
+
+use crate::core::{Fire, Shot};
+
+const FRAME_DURATION: Duration = Duration::from_millis(100);
+const FRAMES: u32 = 8;
+
+/// Briefly highlights the cell that was just fired at.
+#[derive(Clone, Copy, Debug)]
+pub struct Animation {
+    pub shot: Shot,
+    started: Instant,
+}
+
+impl Animation {
+    pub fn new(shot: Shot, now: Instant) -> Self {
+        Self { shot, started: now }
+    }
+
+    pub fn is_done(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) >= FRAME_DURATION * FRAMES
+    }
+
+    pub fn effect(&self, now: Instant) -> Effect {
+        let elapsed = now.saturating_duration_since(self.started);
+        Effect {
+            fire: self.shot.fire,
+            frame: (elapsed.as_millis() / FRAME_DURATION.as_millis()) as usize,
+        }
+    }
+}
+
+/// The current frame of an animation, as needed to render a single cell.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Effect {
+    pub fire: Fire,
+    pub frame: usize,
+}
@@ -1,4 +1,12 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use std::{
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
 use eyre::Result;
 use ratatui::{
     buffer::Buffer,
@@ -14,16 +22,91 @@ use ratatui::{
     Frame,
 };
 
-use crate::core::{Active, Game, GameResult};
+use crate::{
+    core::{Active, Difficulty, Fire, Game, GameResult, Point, Shot, Timeout},
+    profile::{Outcome, Profiles},
+};
+
+use super::{
+    animation::Animation,
+    config::{Action, Config},
+    cursor::Cursor,
+    heatmap::heatmap,
+    terminal,
+    widgets::{CellSize, HelpWidget, LogWidget, PlayerWidget, StatsWidget},
+};
+
+/// How often the screen is redrawn when nothing else happens.
+const TICK: Duration = Duration::from_millis(50);
+
+/// How long a bot "thinks" before firing.
+const BOT_DELAY: Duration = Duration::from_millis(600);
+
+/// How many shots the log scrolls at a time.
+const LOG_PAGE: usize = 5;
 
-use super::{cursor::Cursor, terminal, widgets::PlayerWidget};
+/// Width of the log panel, which is only shown if there is room for it.
+const LOG_WIDTH: u16 = 44;
+
+/// How much of each grid must be visible when the grids scroll.
+const MIN_VISIBLE_CELLS: usize = 5;
+
+/// How long a flash message is shown.
+const FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Eq, PartialEq)]
+enum Mode {
+    /// The first player is controlled by the user, the second by a bot.
+    Play,
+
+    /// Both players are bots, and the user sees both fleets, lagging `delay` turns behind.
+    Spectate { delay: usize },
+}
+
+/// Going through a finished game move by move.
+#[derive(Debug, Eq, PartialEq)]
+struct Review {
+    /// How many moves of the game are shown.
+    step: usize,
+    heatmap: bool,
+}
+
+/// Why the app stopped running.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Exit {
+    Quit,
+
+    /// Back to the menu, to play again.
+    Menu,
+}
 
 #[derive(Debug)]
 pub struct App {
     game: Game<Active>,
+    mode: Mode,
+    config: Config,
+    difficulty: Difficulty,
+    profiles: Option<PathBuf>,
+    log: Vec<Shot>,
+    log_scroll: usize,
     cursor: Cursor,
+    entry: Option<String>,
     message: Option<String>,
-    exit: bool,
+    exit: Option<Exit>,
+    area: Rect,
+    now: Instant,
+    turn: usize,
+    bot_fires_at: Option<Instant>,
+    paused: bool,
+    review: Option<Review>,
+    help: bool,
+    animations: Vec<Animation>,
+    animated: usize,
+    flash: Option<(String, Instant)>,
+
+    /// Set when the turn ran out of time during the current tick, so that the flash
+    /// about the shot fired for the player also says so.
+    timed_out: bool,
 }
 
 impl Default for App {
@@ -35,92 +118,559 @@ impl Default for App {
 
 impl App {
     pub fn new(game: Game<Active>) -> Self {
+        Self::with_mode(game, Mode::Play)
+    }
+
+    pub fn spectate(game: Game<Active>, delay: usize) -> Self {
+        Self::with_mode(game, Mode::Spectate { delay })
+    }
+
+    fn with_mode(mut game: Game<Active>, mode: Mode) -> Self {
         assert_eq!(game.players.len(), 2);
         let grid_size = game.grid_size;
+        let now = Instant::now();
+        game.start_turn(now);
+        let bot_fires_at = match mode {
+            Mode::Play => None,
+            Mode::Spectate { .. } => Some(now + BOT_DELAY),
+        };
         App {
             game,
+            mode,
+            config: Config::default(),
+            difficulty: Difficulty::default(),
+            profiles: None,
+            log: vec![],
+            log_scroll: 0,
             cursor: Cursor::new(grid_size, grid_size),
+            entry: None,
             message: None,
-            exit: false,
+            exit: None,
+            area: Rect::default(),
+            now,
+            turn: 0,
+            bot_fires_at,
+            paused: false,
+            review: None,
+            help: false,
+            animations: vec![],
+            animated: 0,
+            flash: None,
+            timed_out: false,
         }
     }
 
-    pub fn run(&mut self, terminal: &mut terminal::Type) -> Result<()> {
-        while !self.exit {
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// How well the bots play.
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Where to record the result of the game for the player's stats.
+    pub fn profiles(mut self, path: Option<PathBuf>) -> Self {
+        self.profiles = path;
+        self
+    }
+
+    pub fn run(&mut self, terminal: &mut terminal::Type) -> Result<Exit> {
+        loop {
             terminal.draw(|frame| self.render_frame(frame))?;
             self.handle_events()?;
+            self.tick(Instant::now());
+            if let Some(exit) = self.exit {
+                return Ok(exit);
+            }
         }
-        Ok(())
     }
 
-    fn render_frame(&self, frame: &mut Frame) {
-        frame.render_widget(self, frame.size());
+    fn render_frame(&mut self, frame: &mut Frame) {
+        self.area = frame.size();
+        frame.render_widget(&*self, self.area);
+    }
+
+    /// Fits the players' grids and the log on the screen, if the terminal is large enough.
+    ///
+    /// Whole grids are preferred, first side by side and then stacked, before trying again
+    /// with compact cells. If not even those fit, the grids scroll to follow the action.
+    fn layout(&self, area: Rect) -> Result<Screen, (u16, u16)> {
+        let inner_area = Block::default().borders(Borders::ALL).inner(area);
+        let player_size = |cell_size| {
+            let widget =
+                PlayerWidget::new("", &self.game.players[0].grid, false, None).cell_size(cell_size);
+            (widget.box_width() as u16, widget.box_height() as u16)
+        };
+        let fits =
+            |(width, height): (u16, u16)| width <= inner_area.width && height <= inner_area.height;
+
+        for cell_size in [CellSize::Normal, CellSize::Compact] {
+            let (width, height) = player_size(cell_size);
+            if fits((2 * width, height)) {
+                return Ok(Screen::new(
+                    inner_area,
+                    Direction::Horizontal,
+                    2 * width,
+                    cell_size,
+                ));
+            }
+            if fits((width, 2 * height)) {
+                return Ok(Screen::new(
+                    inner_area,
+                    Direction::Vertical,
+                    width,
+                    cell_size,
+                ));
+            }
+        }
+
+        // Compact cells take a single character, so each hidden column or row saves one.
+        let (full_width, full_height) = player_size(CellSize::Compact);
+        let hidden = self.game.grid_size.saturating_sub(MIN_VISIBLE_CELLS) as u16;
+        let (width, height) = (full_width - hidden, full_height - hidden);
+        let scrolling = |direction, players_width: u16| {
+            let players_width = players_width.min(inner_area.width);
+            Screen::new(inner_area, direction, players_width, CellSize::Compact)
+        };
+        if fits((2 * width, height)) {
+            Ok(scrolling(Direction::Horizontal, 2 * full_width))
+        } else if fits((width, 2 * height)) {
+            Ok(scrolling(Direction::Vertical, full_width))
+        } else {
+            // Including the frame around everything
+            Err((2 * width + 2, height + 2))
+        }
     }
 
     fn handle_events(&mut self) -> Result<()> {
-        match event::read()? {
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                self.handle_key_event(key_event)
+        if event::poll(TICK)? {
+            match event::read()? {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    self.handle_key_event(key_event)
+                }
+                Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+                _ => {}
             }
-            _ => {}
         }
         Ok(())
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('q') | KeyCode::Esc => self.exit(),
-            KeyCode::Up | KeyCode::Char('w') => {
-                self.cursor.up();
+        if self.entry.is_some() {
+            return self.handle_entry_key_event(key_event);
+        }
+        let Some(action) = self.action(key_event.code) else {
+            return;
+        };
+        if self.help {
+            if let Action::Help | Action::Quit = action {
+                self.help = false;
             }
-            KeyCode::Left | KeyCode::Char('a') => {
-                self.cursor.left();
+            return;
+        }
+        if self.review.is_some() {
+            return self.handle_review_action(action);
+        }
+        match action {
+            Action::Quit => self.exit = Some(Exit::Quit),
+            Action::Review => {
+                self.review = Some(Review {
+                    step: self.log.len(),
+                    heatmap: true,
+                });
             }
-            KeyCode::Down | KeyCode::Char('s') => {
-                self.cursor.down();
+            Action::Confirm => self.exit = Some(Exit::Menu),
+            Action::Help => self.help = true,
+            Action::ScrollUp => {
+                self.log_scroll = (self.log_scroll + LOG_PAGE).min(self.visible_log().len());
             }
-            KeyCode::Right | KeyCode::Char('d') => {
-                self.cursor.right();
+            Action::ScrollDown => {
+                self.log_scroll = self.log_scroll.saturating_sub(LOG_PAGE);
             }
-            KeyCode::Char(' ') => {
-                if self.game.result().is_some() {
-                    return; // Game has ended
-                }
+            Action::Pause => self.toggle_pause(),
+            Action::Up => self.cursor.up(),
+            Action::Left => self.cursor.left(),
+            Action::Down => self.cursor.down(),
+            Action::Right => self.cursor.right(),
+            Action::Fire => self.fire(),
+            Action::Type => self.entry = Some(String::new()),
+        }
+    }
 
-                if let Some(_fire) = self.game.players[1].fire_at(self.cursor.point) {
-                    self.game.players[0].fire_at_random();
-                }
+    /// Steps through the moves of a finished game.
+    fn handle_review_action(&mut self, action: Action) {
+        let Some(review) = &mut self.review else {
+            return;
+        };
+        match action {
+            Action::Left => review.step = review.step.saturating_sub(1),
+            Action::Right => review.step = (review.step + 1).min(self.log.len()),
+            Action::Up => review.step = 0,
+            Action::Down => review.step = self.log.len(),
+            Action::Fire => review.heatmap = !review.heatmap,
+            Action::Review => self.review = None,
+            Action::Confirm => self.exit = Some(Exit::Menu),
+            Action::Help => self.help = true,
+            Action::Quit => self.exit = Some(Exit::Quit),
+            _ => {}
+        }
+    }
 
-                match self.game.result() {
-                    Some(GameResult::Winner(player)) => {
-                        self.message = Some(format!("{} won!", player.name));
-                    }
-                    Some(GameResult::Draw) => {
-                        self.message = Some("It's a draw!".into());
+    /// The actions available in the current mode, in the order they are shown.
+    fn actions(&self) -> &'static [Action] {
+        if self.review.is_some() {
+            return &[
+                Action::Left,
+                Action::Right,
+                Action::Up,
+                Action::Down,
+                Action::Fire,
+                Action::Review,
+                Action::Confirm,
+                Action::Help,
+                Action::Quit,
+            ];
+        }
+        if self.game.result().is_some() {
+            return &[
+                Action::Review,
+                Action::Confirm,
+                Action::ScrollUp,
+                Action::ScrollDown,
+                Action::Help,
+                Action::Quit,
+            ];
+        }
+        match self.mode {
+            Mode::Play => &[
+                Action::Up,
+                Action::Left,
+                Action::Down,
+                Action::Right,
+                Action::Fire,
+                Action::Type,
+                Action::ScrollUp,
+                Action::ScrollDown,
+                Action::Help,
+                Action::Quit,
+            ],
+            Mode::Spectate { .. } => &[
+                Action::Pause,
+                Action::ScrollUp,
+                Action::ScrollDown,
+                Action::Help,
+                Action::Quit,
+            ],
+        }
+    }
+
+    fn action(&self, code: KeyCode) -> Option<Action> {
+        self.actions()
+            .iter()
+            .copied()
+            .find(|action| self.config.keys.is(*action, code))
+    }
+
+    /// Hovering over the opponent's grid moves the cursor, and clicking fires.
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        if self.mode != Mode::Play || self.entry.is_some() || self.help {
+            return;
+        }
+        let Some(point) = self.point_at(mouse_event.column, mouse_event.row) else {
+            return;
+        };
+        match mouse_event.kind {
+            MouseEventKind::Moved => {
+                self.cursor.move_to(point);
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.cursor.move_to(point);
+                self.fire();
+            }
+            _ => {}
+        }
+    }
+
+    /// Finds the cell of the opponent's grid at the given screen position.
+    fn point_at(&self, column: u16, row: u16) -> Option<Point> {
+        let screen = self.layout(self.area).ok()?;
+        let opponent = &self.game.players[1];
+        PlayerWidget::new(
+            &opponent.name,
+            &opponent.grid,
+            false,
+            Some(self.cursor.point),
+        )
+        .cell_size(screen.cell_size)
+        .point_at(screen.players[1], column, row)
+    }
+
+    /// Handles typing in coordinates like "C5" to fire at.
+    fn handle_entry_key_event(&mut self, key_event: KeyEvent) {
+        let Some(entry) = &mut self.entry else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.entry = None,
+            KeyCode::Backspace => {
+                entry.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() && entry.len() < 3 => {
+                entry.push(c.to_ascii_uppercase());
+            }
+            KeyCode::Enter => {
+                let error = match entry.parse::<Point>() {
+                    Ok(point) if self.cursor.move_to(point) => None,
+                    Ok(point) => Some(format!("{} is off the grid", point)),
+                    Err(err) => Some(err.to_string()),
+                };
+                match error {
+                    Some(error) => self.flash = Some((error, Instant::now())),
+                    None => {
+                        self.entry = None;
+                        self.fire();
                     }
-                    None => {}
                 }
             }
             _ => {}
         }
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    fn fire(&mut self) {
+        if self.game.result().is_some() || self.turn != 0 {
+            return; // Game has ended, or the bot is about to fire
+        }
+
+        if let Some(shot) = self.game.fire(0, 1, self.cursor.point) {
+            self.log.push(shot);
+            self.end_turn(Instant::now());
+        }
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.bot_fires_at = match self.paused {
+            true => None,
+            false => Some(Instant::now() + BOT_DELAY),
+        };
+    }
+
+    /// Moves the game forward in time, letting bots fire and animations play.
+    fn tick(&mut self, now: Instant) {
+        self.now = now;
+
+        if self.bot_fires_at.is_some_and(|t| now >= t) {
+            self.bot_fires_at = None;
+            let strategy = self.difficulty.strategy();
+            if let Some(shot) = self.game.fire_with(strategy, self.turn, 1 - self.turn) {
+                self.log.push(shot);
+            }
+            self.end_turn(now);
+        }
+        self.enforce_shot_clock(now);
+
+        let visible = self.visible_log().len();
+        if self.log_scroll > 0 {
+            // Keep the log where the user scrolled to
+            self.log_scroll += visible - self.animated;
+        }
+        for i in self.animated..visible {
+            let shot = self.visible_log()[i];
+            self.animations.push(Animation::new(shot, now));
+            if let Some(text) = self.describe(&shot) {
+                let text = match self.timed_out {
+                    true => format!("Time's up! {}", text),
+                    false => text,
+                };
+                self.flash = Some((text, now));
+            }
+        }
+        self.animated = visible;
+        self.timed_out = false;
+        self.animations.retain(|a| !a.is_done(now));
+        if self
+            .flash
+            .as_ref()
+            .is_some_and(|(_, t)| now.saturating_duration_since(*t) >= FLASH_DURATION)
+        {
+            self.flash = None;
+        }
+    }
+
+    fn end_turn(&mut self, now: Instant) {
+        self.game.end_turn(self.turn);
+        self.check_result();
+        if self.game.result().is_some() {
+            return;
+        }
+        self.turn = 1 - self.turn;
+        self.game.start_turn(now);
+        if self.turn == 1 || (self.mode != Mode::Play && !self.paused) {
+            self.bot_fires_at = Some(now + BOT_DELAY);
+        }
+    }
+
+    fn enforce_shot_clock(&mut self, now: Instant) {
+        if self.mode != Mode::Play || self.turn != 0 {
+            return; // Bots never run out of time
+        }
+        match self.game.enforce_shot_clock(now, 0, 1) {
+            Some(Timeout::Fired(shot)) => self.log.push(shot),
+            Some(Timeout::Skipped) => {}
+            Some(Timeout::Forfeited) => {}
+            None => return,
+        }
+        self.flash = Some(("Time's up!".into(), now));
+        self.timed_out = true;
+        self.end_turn(now);
+    }
+
+    fn describe(&self, shot: &Shot) -> Option<String> {
+        let shooter = &self.game.players[shot.shooter].name;
+        let target = &self.game.players[shot.target].name;
+        match shot.fire {
+            Fire::Miss => None,
+            Fire::Hit => Some(format!("{} hit!", shooter)),
+            Fire::Sunk(ship) => Some(format!("{} sunk {}'s {}!", shooter, target, ship)),
+        }
+    }
+
+    fn check_result(&mut self) {
+        match self.game.result() {
+            Some(GameResult::Winner(player)) => {
+                self.message = Some(format!("{} won!", player.name));
+            }
+            Some(GameResult::Draw(_)) => {
+                self.message = Some("It's a draw!".into());
+            }
+            None => return,
+        }
+        if let Err(err) = self.record_result() {
+            let message = self.message.take().unwrap_or_default();
+            self.message = Some(format!("{} Stats not saved: {}", message, err));
+        }
+    }
+
+    /// Adds the result of a game against a bot to the player's profile.
+    fn record_result(&self) -> Result<()> {
+        let (Mode::Play, Some(path)) = (&self.mode, &self.profiles) else {
+            return Ok(());
+        };
+        let player = &self.game.players[0];
+        let outcome = match self.game.result() {
+            Some(GameResult::Winner(winner)) if std::ptr::eq(winner, player) => Outcome::Win,
+            Some(GameResult::Winner(_)) => Outcome::Loss,
+            Some(GameResult::Draw(_)) => Outcome::Draw,
+            None => return Ok(()),
+        };
+        let shots = self.log.iter().filter(|shot| shot.shooter == 0).count();
+        let mut profiles = Profiles::load(path)?;
+        profiles.record(&player.name, self.difficulty, outcome, shots);
+        profiles.save(path)
+    }
+
+    /// The shots the user may know about, which lag behind in spectator mode
+    /// until the game has ended.
+    fn visible_log(&self) -> &[Shot] {
+        match self.mode {
+            Mode::Spectate { delay } if self.game.result().is_none() => {
+                &self.log[..self.log.len().saturating_sub(delay)]
+            }
+            _ => &self.log,
+        }
+    }
+}
+
+/// Where things go on the screen.
+struct Screen {
+    players: Rc<[Rect]>,
+    log: Option<Rect>,
+    cell_size: CellSize,
+}
+
+impl Screen {
+    /// Puts the players next to each other in `direction`, with the log to the right if the
+    /// players leave room for it.
+    fn new(area: Rect, direction: Direction, players_width: u16, cell_size: CellSize) -> Self {
+        let (players_area, log) = match area.width.saturating_sub(players_width) {
+            room if room >= LOG_WIDTH => {
+                let rects = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), Constraint::Length(LOG_WIDTH)])
+                    .split(area);
+                (rects[0], Some(rects[1]))
+            }
+            _ => (area, None),
+        };
+        let players = Layout::default()
+            .direction(direction)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(players_area);
+        Self {
+            players,
+            log,
+            cell_size,
+        }
     }
 }
 
 impl Widget for &App {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let title = Title::from(" Slagskip ".bold().yellow());
-        let instructions = Title::from(Line::from(vec![
-            " Move ".into(),
-            "WASD or arrows".blue().bold(),
-            " Fire ".into(),
-            "<Space>".blue().bold(),
-            " Quit ".into(),
-            "<Q> ".blue().bold(),
-        ]));
+        let hints: &[(&str, &[Action])] = match self.mode {
+            _ if self.review.is_some() => &[
+                ("Step", &[Action::Left, Action::Right]),
+                ("Start/end", &[Action::Up, Action::Down]),
+                ("Heatmap", &[Action::Fire]),
+                ("Back", &[Action::Review]),
+                ("Play again", &[Action::Confirm]),
+                ("Help", &[Action::Help]),
+                ("Quit", &[Action::Quit]),
+            ],
+            _ if self.game.result().is_some() => &[
+                ("Review", &[Action::Review]),
+                ("Play again", &[Action::Confirm]),
+                ("Scroll log", &[Action::ScrollUp, Action::ScrollDown]),
+                ("Help", &[Action::Help]),
+                ("Quit", &[Action::Quit]),
+            ],
+            Mode::Play => &[
+                (
+                    "Move",
+                    &[Action::Up, Action::Left, Action::Down, Action::Right],
+                ),
+                ("Fire", &[Action::Fire]),
+                ("Type target", &[Action::Type]),
+                ("Help", &[Action::Help]),
+                ("Quit", &[Action::Quit]),
+            ],
+            Mode::Spectate { .. } if self.paused => &[
+                ("Resume", &[Action::Pause]),
+                ("Scroll log", &[Action::ScrollUp, Action::ScrollDown]),
+                ("Help", &[Action::Help]),
+                ("Quit", &[Action::Quit]),
+            ],
+            Mode::Spectate { .. } => &[
+                ("Pause", &[Action::Pause]),
+                ("Scroll log", &[Action::ScrollUp, Action::ScrollDown]),
+                ("Help", &[Action::Help]),
+                ("Quit", &[Action::Quit]),
+            ],
+        };
+        let mut instructions = vec![];
+        for (label, actions) in hints {
+            instructions.push(format!(" {} ", label).into());
+            instructions.push(
+                format!("<{}>", self.config.keys.describe(actions))
+                    .blue()
+                    .bold(),
+            );
+        }
+        instructions.push(" ".into());
+        let instructions = Title::from(Line::from(instructions));
         let frame_block = Block::default()
             .title(title.alignment(Alignment::Center))
             .title(
@@ -130,30 +680,86 @@ impl Widget for &App {
             )
             .borders(Borders::ALL)
             .border_set(border::THICK);
+        let screen = match self.layout(area) {
+            Ok(screen) => screen,
+            Err((width, height)) => {
+                let text = vec![
+                    Line::from("Terminal too small".bold()),
+                    Line::from(format!("Needs {}x{}", width, height)),
+                ];
+                Paragraph::new(text)
+                    .alignment(Alignment::Center)
+                    .render(centered_rect(area.width, 2, area), buf);
+                return;
+            }
+        };
         frame_block.render(area, buf);
+        let shots = match &self.review {
+            Some(review) => &self.log[..review.step],
+            None => self.visible_log(),
+        };
+        match (screen.log, &self.review) {
+            (Some(log_rect), Some(_)) => StatsWidget::new(&self.game, shots)
+                .moves(self.log.len())
+                .render(log_rect, buf),
+            (Some(log_rect), None) => LogWidget::new(&self.game, shots)
+                .scroll(self.log_scroll)
+                .theme(self.config.theme)
+                .render(log_rect, buf),
+            (None, _) => {}
+        }
 
-        let players_rects = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .vertical_margin(1)
-            .horizontal_margin(1)
-            .split(area);
-
+        let grids = self.game.grids_after(shots);
+        let time_left = match (self.game.result(), self.turn) {
+            (None, 0) => self.game.time_left(self.now),
+            _ => None,
+        };
         for (i, player) in self.game.players.iter().enumerate() {
-            PlayerWidget::new(
-                player,
-                i == 0,
-                if i == 0 {
-                    None
-                } else {
-                    Some(self.cursor.point)
-                },
-            )
-            .render(players_rects[i], buf);
+            let (with_ships, cursor, time_left, prompt) = match self.mode {
+                // All ships are revealed in the review
+                _ if self.review.is_some() => (true, None, None, None),
+                Mode::Play if i == 0 => (true, None, time_left, None),
+                Mode::Play => (false, Some(self.cursor.point), None, self.entry.as_deref()),
+                Mode::Spectate { .. } => (true, None::<Point>, None, None),
+            };
+            let difficulty = match self.mode {
+                Mode::Play if i == 0 => None,
+                _ => Some(self.difficulty),
+            };
+            let effects = self
+                .animations
+                .iter()
+                .filter(|a| a.shot.target == i && self.review.is_none())
+                .map(|a| (a.shot.point, a.effect(self.now)))
+                .collect();
+            let heatmap = match &self.review {
+                Some(review) if review.heatmap => heatmap(shots, i),
+                _ => vec![],
+            };
+            let last_shot = shots
+                .iter()
+                .rfind(|shot| shot.target == i)
+                .map(|shot| shot.point);
+            PlayerWidget::new(&player.name, &grids[i], with_ships, cursor)
+                .difficulty(difficulty)
+                .time_left(time_left)
+                .prompt(prompt)
+                .last_shot(last_shot)
+                .effects(effects)
+                .heatmap(heatmap)
+                .theme(self.config.theme)
+                .cell_size(screen.cell_size)
+                .render(screen.players[i], buf);
         }
 
-        if let Some(message) = &self.message {
-            let message_area = centered_rect(20, 3, area);
+        let flash = self.flash.as_ref().map(|(flash, _)| flash);
+        let message = match self.review {
+            Some(_) => None,
+            None => self.message.as_ref().or(flash),
+        };
+        if let Some(message) = message {
+            let width = (Line::raw(message.as_str()).width() + 4).max(20) as u16;
+            let message_area = centered_rect(width, 3, area);
             let message_paragraph = Paragraph::new(message.clone())
                 .block(
                     Block::default()
@@ -166,31 +772,34 @@ impl Widget for &App {
                 .style(Style::default());
             message_paragraph.render(message_area, buf);
         }
+
+        if self.help {
+            let help =
+                HelpWidget::new(&self.config.keys, &self.game.rules).theme(self.config.theme);
+            let (width, height) = help.size();
+            help.render(centered_rect(width, height, area), buf);
+        }
     }
 }
 
+/// A rectangle of the given size in the middle of `area`, shrunk to fit if needed.
 pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
-    let vertical_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(area.height / 2 - height / 2),
-            Constraint::Length(height),
-            Constraint::Length(area.height / 2 - height / 2),
-        ])
-        .split(area);
-    let horizontal_layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Length(area.width / 2 - width / 2),
-            Constraint::Length(width),
-            Constraint::Length(area.width / 2 - width / 2),
-        ])
-        .split(vertical_layout[1]);
-    horizontal_layout[1]
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width / 2).saturating_sub(width / 2),
+        y: area.y + (area.height / 2).saturating_sub(height / 2),
+        width,
+        height,
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use strum::IntoEnumIterator;
+
+    use crate::core::{self, OnTimeout, Resolution, Rules, Ship, ShotClock};
+
     use super::*;
 
     #[test]
@@ -199,7 +808,455 @@ mod tests {
 
         app.handle_key_event(KeyCode::Char('q').into());
 
-        assert_eq!(app.exit, true);
+        assert_eq!(app.exit, Some(Exit::Quit));
+        Ok(())
+    }
+
+    #[test]
+    fn play_again_when_game_has_ended() -> Result<()> {
+        let mut app = App::new(bot_game()?);
+        app.handle_key_event(KeyCode::Enter.into());
+        assert_eq!(app.exit, None);
+
+        // Without any ships, the default game is a draw from the start
+        let mut app = App::default();
+        app.handle_key_event(KeyCode::Enter.into());
+        assert_eq!(app.exit, Some(Exit::Menu));
         Ok(())
     }
+
+    /// Lets two cheating bots play a whole game.
+    fn finished_app() -> Result<App> {
+        let mut app = App::spectate(bot_game()?, 0).difficulty(Difficulty::Cheater);
+        let mut now = Instant::now();
+        while app.game.result().is_none() {
+            now += BOT_DELAY;
+            app.tick(now);
+        }
+        Ok(app)
+    }
+
+    #[test]
+    fn record_result_in_profile() -> Result<()> {
+        let path = std::env::temp_dir()
+            .join(format!("slagskip-app-test-{}", std::process::id()))
+            .join("profiles.toml");
+        let mut app = App::new(bot_game()?)
+            .difficulty(Difficulty::Cheater)
+            .profiles(Some(path.clone()));
+        let mut points = app.game.players[1]
+            .grid
+            .points()
+            .collect::<Vec<_>>()
+            .into_iter();
+        let mut now = Instant::now();
+        while app.game.result().is_none() {
+            if app.turn == 0 {
+                app.cursor.point = points.next().unwrap();
+                app.fire();
+            } else {
+                now += BOT_DELAY;
+                app.tick(now);
+            }
+        }
+
+        let profiles = Profiles::load(&path);
+        std::fs::remove_dir_all(path.parent().unwrap())?;
+        let profile = profiles?.get("Alice").unwrap().clone();
+        let (difficulty, record) = profile.records().next().unwrap();
+        assert_eq!(difficulty, Difficulty::Cheater);
+        assert_eq!((record.games(), record.losses), (1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn review_finished_game() -> Result<()> {
+        let mut app = App::spectate(bot_game()?, 0);
+        app.handle_key_event(KeyCode::Char('r').into());
+        assert_eq!(app.review, None);
+
+        let mut app = finished_app()?;
+        let moves = app.log.len();
+        app.handle_key_event(KeyCode::Char('r').into());
+        assert_eq!(
+            app.review,
+            Some(Review {
+                step: moves,
+                heatmap: true
+            })
+        );
+
+        app.handle_key_event(KeyCode::Left.into());
+        assert_eq!(app.review.as_ref().unwrap().step, moves - 1);
+        app.handle_key_event(KeyCode::Up.into());
+        app.handle_key_event(KeyCode::Right.into());
+        assert_eq!(app.review.as_ref().unwrap().step, 1);
+        app.handle_key_event(KeyCode::Down.into());
+        app.handle_key_event(KeyCode::Right.into());
+        assert_eq!(app.review.as_ref().unwrap().step, moves);
+
+        app.handle_key_event(KeyCode::Char(' ').into());
+        assert!(!app.review.as_ref().unwrap().heatmap);
+
+        app.handle_key_event(KeyCode::Char('r').into());
+        assert_eq!(app.review, None);
+        Ok(())
+    }
+
+    fn bot_game() -> Result<Game<Active>> {
+        let mut game = Game::new(10);
+        game.add_player("Alice").place_ships_randomly()?;
+        game.add_player("Bob").place_ships_randomly()?;
+        game.start()
+    }
+
+    #[test]
+    fn bot_fires_back_when_sunk_with_simultaneous_resolution() -> Result<()> {
+        let rules = Rules {
+            fleet: vec![Ship::Destroyer],
+            resolution: Resolution::Simultaneous,
+            ..Rules::for_grid(4)
+        };
+        let mut game = Game::with_rules(4, rules)?;
+        for (name, y) in [("Alice", 0), ("Bob", 3)] {
+            game.add_player(name).place_ship(
+                Ship::Destroyer,
+                Point(0, y),
+                core::Direction::Horizontal,
+            )?;
+        }
+        let mut app = App::new(game.start()?).difficulty(Difficulty::Easy);
+
+        app.cursor.point = Point(0, 3);
+        app.fire();
+        app.tick(Instant::now() + BOT_DELAY);
+        app.cursor.point = Point(1, 3);
+        app.fire();
+
+        // Bob is sunk, but the game is not over until the bot has fired back
+        assert!(app.message.is_none());
+        assert_eq!(app.turn, 1);
+        app.tick(Instant::now() + BOT_DELAY);
+        assert_eq!(app.log.len(), 4);
+        assert!(app.message.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn spectator_view_lags_behind() -> Result<()> {
+        let mut app = App::spectate(bot_game()?, 3);
+        let mut now = Instant::now();
+
+        for _ in 0..4 {
+            now += BOT_DELAY;
+            app.tick(now);
+        }
+
+        assert_eq!(app.log.len(), 4);
+        assert_eq!(app.visible_log().len(), 1);
+        assert_eq!(app.animations.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn spectator_can_pause() -> Result<()> {
+        let mut app = App::spectate(bot_game()?, 0);
+
+        app.handle_key_event(KeyCode::Char(' ').into());
+        app.tick(Instant::now() + BOT_DELAY);
+        assert!(app.log.is_empty());
+
+        app.handle_key_event(KeyCode::Char(' ').into());
+        app.tick(Instant::now() + BOT_DELAY);
+        assert_eq!(app.log.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn bot_fires_after_delay() -> Result<()> {
+        let mut app = App::new(bot_game()?);
+        let now = Instant::now();
+
+        app.handle_key_event(KeyCode::Char(' ').into());
+        assert_eq!(app.log.len(), 1);
+
+        // The player has to wait for the bot
+        app.handle_key_event(KeyCode::Right.into());
+        app.handle_key_event(KeyCode::Char(' ').into());
+        app.tick(now);
+        assert_eq!(app.log.len(), 1);
+
+        app.tick(now + BOT_DELAY * 2);
+        assert_eq!(app.log.len(), 2);
+        assert_eq!(app.log[1].shooter, 1);
+
+        app.handle_key_event(KeyCode::Char(' ').into());
+        assert_eq!(app.log.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn log_scrolls_back() -> Result<()> {
+        let mut app = App::spectate(bot_game()?, 0);
+        let mut now = Instant::now();
+        for _ in 0..8 {
+            now += BOT_DELAY;
+            app.tick(now);
+        }
+
+        app.handle_key_event(KeyCode::PageUp.into());
+        assert_eq!(app.log_scroll, LOG_PAGE);
+        app.handle_key_event(KeyCode::PageUp.into());
+        assert_eq!(app.log_scroll, 8);
+
+        // New shots do not move the scrolled log
+        now += BOT_DELAY;
+        app.tick(now);
+        assert_eq!(app.log_scroll, 9);
+
+        app.handle_key_event(KeyCode::PageDown.into());
+        app.handle_key_event(KeyCode::PageDown.into());
+        assert_eq!(app.log_scroll, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn vim_key_bindings() -> Result<()> {
+        let config = "[keys]\npreset = \"vim\"".parse::<Config>()?;
+        let mut app = App::new(bot_game()?).config(config);
+        let start = app.cursor.point;
+
+        app.handle_key_event(KeyCode::Char('l').into());
+        app.handle_key_event(KeyCode::Char('j').into());
+        assert_eq!(app.cursor.point, Point(start.0 + 1, start.1 + 1));
+
+        // WASD is not bound to anything
+        app.handle_key_event(KeyCode::Char('a').into());
+        assert_eq!(app.cursor.point, Point(start.0 + 1, start.1 + 1));
+        Ok(())
+    }
+
+    #[test]
+    fn fire_at_typed_coordinates() -> Result<()> {
+        let mut app = App::new(bot_game()?);
+
+        for c in ":c2".chars() {
+            app.handle_key_event(KeyCode::Char(c).into());
+        }
+        assert_eq!(app.entry.as_deref(), Some("C2"));
+        app.handle_key_event(KeyCode::Enter.into());
+
+        assert_eq!(app.entry, None);
+        assert_eq!(app.cursor.point, Point(2, 1));
+        assert_eq!(app.log.len(), 1);
+        assert_eq!(app.log[0].point, Point(2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn typed_coordinates_off_the_grid() -> Result<()> {
+        let mut app = App::new(bot_game()?);
+        let cursor = app.cursor.point;
+
+        for c in ":K1".chars() {
+            app.handle_key_event(KeyCode::Char(c).into());
+        }
+        app.handle_key_event(KeyCode::Enter.into());
+
+        // The entry stays open to be corrected
+        assert_eq!(app.entry.as_deref(), Some("K1"));
+        assert_eq!(app.flash.as_ref().unwrap().0, "K1 is off the grid");
+        assert_eq!(app.cursor.point, cursor);
+        assert!(app.log.is_empty());
+
+        app.handle_key_event(KeyCode::Esc.into());
+        assert_eq!(app.entry, None);
+        assert_eq!(app.exit, None);
+        Ok(())
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn mouse_hover_moves_cursor() -> Result<()> {
+        let mut app = App::new(bot_game()?);
+        app.area = Rect::new(0, 0, 140, 30);
+
+        // B7 on the opponent's grid
+        app.handle_mouse_event(mouse(MouseEventKind::Moved, 61, 17));
+        assert_eq!(app.cursor.point, Point(1, 6));
+
+        // The right border of C7, which belongs to the cell
+        app.handle_mouse_event(mouse(MouseEventKind::Moved, 66, 17));
+        assert_eq!(app.cursor.point, Point(2, 6));
+
+        // The column labels and the player's own grid are not targets
+        app.handle_mouse_event(mouse(MouseEventKind::Moved, 61, 3));
+        app.handle_mouse_event(mouse(MouseEventKind::Moved, 14, 17));
+        assert_eq!(app.cursor.point, Point(2, 6));
+        Ok(())
+    }
+
+    #[test]
+    fn mouse_click_fires() -> Result<()> {
+        let mut app = App::new(bot_game()?);
+        app.area = Rect::new(0, 0, 140, 30);
+
+        app.handle_mouse_event(mouse(MouseEventKind::Down(MouseButton::Left), 61, 17));
+
+        assert_eq!(app.log.len(), 1);
+        assert_eq!(app.log[0].point, Point(1, 6));
+        Ok(())
+    }
+
+    #[test]
+    fn animations_end() -> Result<()> {
+        let mut app = App::new(bot_game()?);
+        let now = Instant::now();
+
+        app.handle_key_event(KeyCode::Char(' ').into());
+        app.tick(now);
+        assert_eq!(app.animations.len(), 1);
+
+        // The bot fires in the meantime, starting a new animation
+        app.tick(now + Duration::from_secs(5));
+        assert_eq!(app.animations.len(), 1);
+        assert_eq!(app.animations[0].shot.shooter, 1);
+
+        app.tick(now + Duration::from_secs(10));
+        assert!(app.animations.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn shot_clock_fires_for_player() -> Result<()> {
+        let mut game = Game::with_rules(
+            10,
+            Rules {
+                shot_clock: Some(ShotClock {
+                    limit: Duration::from_secs(10),
+                    on_timeout: OnTimeout::RandomShot,
+                }),
+                ..Rules::for_grid(10)
+            },
+        )?;
+        game.add_player("Alice").place_ships_randomly()?;
+        game.add_player("Bob").place_ships_randomly()?;
+        let mut app = App::new(game.start()?);
+
+        let now = Instant::now();
+        app.tick(now);
+        assert!(app.log.is_empty());
+
+        app.tick(now + Duration::from_secs(10));
+        assert_eq!(app.log.len(), 1);
+        let expected = match app.log[0].fire {
+            Fire::Miss => "Time's up!".to_string(),
+            Fire::Hit => "Time's up! Alice hit!".to_string(),
+            Fire::Sunk(ship) => format!("Time's up! Alice sunk Bob's {}!", ship),
+        };
+        assert_eq!(app.flash.as_ref().unwrap().0, expected);
+
+        app.tick(now + Duration::from_secs(10) + BOT_DELAY);
+        assert_eq!(app.log.len(), 2);
+        assert_eq!((app.log[0].shooter, app.log[1].shooter), (0, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn help_shows_all_key_bindings() -> Result<()> {
+        let mut app = App::new(bot_game()?);
+        app.handle_key_event(KeyCode::Char('?').into());
+        assert!(app.help);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 140, 30));
+        app.render(buf.area, &mut buf);
+        let text = buf
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        for action in Action::iter() {
+            assert!(text.contains(action.description()), "{}", action);
+        }
+        assert!(text.contains("classic (ships may touch)"));
+        assert!(text.contains("Battleship 4"));
+
+        // Keys only close the help while it is shown
+        app.handle_key_event(KeyCode::Char(' ').into());
+        assert!(app.log.is_empty());
+        app.handle_key_event(KeyCode::Char('q').into());
+        assert!(!app.help);
+        assert_eq!(app.exit, None);
+        Ok(())
+    }
+
+    #[test]
+    fn spectator_cannot_fire() -> Result<()> {
+        let mut app = App::spectate(bot_game()?, 0);
+
+        app.handle_key_event(KeyCode::Right.into());
+
+        assert_eq!(app.cursor.point, Point(5, 5));
+        Ok(())
+    }
+
+    #[test]
+    fn layout_adapts_to_terminal_size() -> Result<()> {
+        let app = App::new(bot_game()?);
+
+        let screen = app.layout(Rect::new(0, 0, 140, 30)).unwrap();
+        assert_eq!(screen.cell_size, CellSize::Normal);
+        assert!(screen.log.is_some());
+        assert_eq!(screen.players[0].y, screen.players[1].y);
+
+        // Too narrow for the grids side by side, so they are stacked without the log
+        let screen = app.layout(Rect::new(0, 0, 50, 60)).unwrap();
+        assert_eq!(screen.cell_size, CellSize::Normal);
+        assert!(screen.log.is_none());
+        assert_eq!(screen.players[0].x, screen.players[1].x);
+
+        let screen = app.layout(Rect::new(0, 0, 80, 24)).unwrap();
+        assert_eq!(screen.cell_size, CellSize::Compact);
+
+        assert_eq!(app.layout(Rect::new(0, 0, 20, 10)).err(), Some((30, 13)));
+        Ok(())
+    }
+
+    #[test]
+    fn large_grid_scrolls() -> Result<()> {
+        let mut game = Game::new(26);
+        game.add_player("Alice").place_ships_randomly()?;
+        game.add_player("Bob").place_ships_randomly()?;
+        let mut app = App::new(game.start()?);
+        app.area = Rect::new(0, 0, 80, 24);
+
+        // The bottom right corner of the grid comes into view when the cursor gets there
+        app.cursor.point = Point(25, 25);
+        let screen = app.layout(app.area).unwrap();
+        assert_eq!(screen.cell_size, CellSize::Compact);
+        let area = screen.players[1];
+        let last_row = (area.top()..area.bottom())
+            .flat_map(|row| (area.left()..area.right()).map(move |column| (column, row)))
+            .filter_map(|(column, row)| app.point_at(column, row))
+            .map(|point| point.1)
+            .max();
+        assert_eq!(last_row, Some(25));
+        Ok(())
+    }
+
+    #[test]
+    fn centered_rect_fits_in_area() {
+        let area = Rect::new(10, 5, 20, 4);
+
+        assert_eq!(centered_rect(10, 2, area), Rect::new(15, 6, 10, 2));
+        assert_eq!(centered_rect(30, 9, area), area);
+    }
 }
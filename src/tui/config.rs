@@ -0,0 +1,354 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crossterm::event::KeyCode;
+use eyre::{eyre, Report, Result, WrapErr};
+use serde::Deserialize;
+
+use super::theme::Theme;
+
+/// User preferences for the terminal UI, read from `config.toml`.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub keys: KeyBindings,
+    pub theme: Theme,
+}
+
+impl Config {
+    /// The config file in the user's config directory, like `~/.config/slagskip/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("slagskip").join("config.toml"))
+    }
+
+    /// Loads the config from the given path, or from the default path if there is a file.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Self::default()),
+            },
+        };
+        let text = fs::read_to_string(&path)
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        text.parse()
+            .wrap_err_with(|| format!("Invalid config in {}", path.display()))
+    }
+}
+
+impl FromStr for Config {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+/// Something the user can do with a key press.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Deserialize,
+    Eq,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Action {
+    Up,
+    Left,
+    Down,
+    Right,
+    Fire,
+    Type,
+    Pause,
+    ScrollUp,
+    ScrollDown,
+    Review,
+    Confirm,
+    Help,
+    Quit,
+}
+
+impl Action {
+    /// What the action does, for the help overlay.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Up => "Move up / first move",
+            Self::Left => "Move left / move back",
+            Self::Down => "Move down / last move",
+            Self::Right => "Move right / move ahead",
+            Self::Fire => "Fire / toggle heatmap",
+            Self::Type => "Type target",
+            Self::Pause => "Pause bots",
+            Self::ScrollUp => "Scroll log back",
+            Self::ScrollDown => "Scroll log ahead",
+            Self::Review => "Review game",
+            Self::Confirm => "Play again",
+            Self::Help => "Toggle help",
+            Self::Quit => "Quit",
+        }
+    }
+
+    /// Whether the action may share keys with the other, as they never apply at the same
+    /// time: fire when playing, pause when spectating.
+    fn may_share_keys(&self, other: Action) -> bool {
+        matches!(
+            (self, other),
+            (Self::Fire, Self::Pause) | (Self::Pause, Self::Fire)
+        )
+    }
+}
+
+/// Which keys trigger which actions.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "KeyBindingsConfig")]
+pub struct KeyBindings(BTreeMap<Action, Vec<Key>>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Preset::default().bindings()
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.0.get(&action).map_or(&[], |keys| keys)
+    }
+
+    pub fn is(&self, action: Action, code: KeyCode) -> bool {
+        self.keys(action).iter().any(|key| key.0 == code)
+    }
+
+    /// Fails if a key triggers more than one action, unless they never apply together.
+    fn check_conflicts(&self) -> Result<()> {
+        let bound = self
+            .0
+            .iter()
+            .flat_map(|(action, keys)| keys.iter().map(move |key| (*action, *key)))
+            .collect::<Vec<_>>();
+        for (i, (action, key)) in bound.iter().enumerate() {
+            let conflict = bound[..i].iter().find(|(other, other_key)| {
+                other_key == key && other != action && !action.may_share_keys(*other)
+            });
+            if let Some((other, _)) = conflict {
+                return Err(eyre!("{} is bound to both {} and {}", key, other, action));
+            }
+        }
+        Ok(())
+    }
+
+    /// Describes the keys for the given actions, like "WASD/↑←↓→".
+    pub fn describe(&self, actions: &[Action]) -> String {
+        let max_keys = actions.iter().map(|a| self.keys(*a).len()).max();
+        let mut alternatives = vec![];
+        for i in 0..max_keys.unwrap_or(0) {
+            let keys = actions
+                .iter()
+                .filter_map(|a| self.keys(*a).get(i))
+                .map(|key| key.to_string())
+                .collect::<Vec<_>>();
+            if keys.len() < actions.len() {
+                continue; // Only complete sets of keys are worth mentioning
+            }
+            let separator = match keys.iter().all(|key| key.chars().count() == 1) {
+                true => "",
+                false => " ",
+            };
+            alternatives.push(keys.join(separator));
+        }
+        alternatives.join("/")
+    }
+}
+
+/// The `[keys]` table of the config file: a preset, with some actions rebound.
+#[derive(Deserialize)]
+struct KeyBindingsConfig {
+    #[serde(default)]
+    preset: Preset,
+    #[serde(flatten)]
+    overrides: BTreeMap<Action, Vec<String>>,
+}
+
+impl TryFrom<KeyBindingsConfig> for KeyBindings {
+    type Error = Report;
+
+    fn try_from(config: KeyBindingsConfig) -> Result<Self> {
+        let mut bindings = config.preset.bindings();
+        for (action, keys) in config.overrides {
+            let keys = keys
+                .iter()
+                .map(|key| key.parse())
+                .collect::<Result<Vec<Key>>>()?;
+            bindings.0.insert(action, keys);
+        }
+        bindings.check_conflicts()?;
+        Ok(bindings)
+    }
+}
+
+/// Ready-made sets of key bindings.
+#[derive(Copy, Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    /// WASD or arrow keys.
+    #[default]
+    Default,
+
+    /// HJKL or arrow keys.
+    Vim,
+}
+
+impl Preset {
+    fn bindings(&self) -> KeyBindings {
+        let (up, left, down, right) = match self {
+            Self::Default => ('w', 'a', 's', 'd'),
+            Self::Vim => ('k', 'h', 'j', 'l'),
+        };
+        let char_or = |c, code| vec![Key(KeyCode::Char(c)), Key(code)];
+        KeyBindings(BTreeMap::from([
+            (Action::Up, char_or(up, KeyCode::Up)),
+            (Action::Left, char_or(left, KeyCode::Left)),
+            (Action::Down, char_or(down, KeyCode::Down)),
+            (Action::Right, char_or(right, KeyCode::Right)),
+            (Action::Fire, vec![Key(KeyCode::Char(' '))]),
+            (Action::Type, vec![Key(KeyCode::Char(':'))]),
+            (Action::Pause, vec![Key(KeyCode::Char(' '))]),
+            (Action::ScrollUp, vec![Key(KeyCode::PageUp)]),
+            (Action::ScrollDown, vec![Key(KeyCode::PageDown)]),
+            (Action::Review, vec![Key(KeyCode::Char('r'))]),
+            (Action::Confirm, vec![Key(KeyCode::Enter)]),
+            (Action::Help, vec![Key(KeyCode::Char('?'))]),
+            (Action::Quit, char_or('q', KeyCode::Esc)),
+        ]))
+    }
+}
+
+/// A key, written in the config as a character or a name like "space" or "pageup".
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Key(pub KeyCode);
+
+impl FromStr for Key {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key(KeyCode::Char(c)));
+        }
+        let code = match s.to_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(eyre!("Unknown key: {}", s)),
+            },
+        };
+        Ok(Key(code))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_uppercase()),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_is_default() -> Result<()> {
+        assert_eq!("".parse::<Config>()?, Config::default());
+        Ok(())
+    }
+
+    #[test]
+    fn vim_preset_with_overrides() -> Result<()> {
+        let config = r#"
+            theme = "monochrome"
+
+            [keys]
+            preset = "vim"
+            fire = ["f", "x"]
+        "#
+        .parse::<Config>()?;
+
+        assert_eq!(config.theme, Theme::Monochrome);
+        assert!(config.keys.is(Action::Up, KeyCode::Char('k')));
+        assert!(config.keys.is(Action::Up, KeyCode::Up));
+        assert!(!config.keys.is(Action::Up, KeyCode::Char('w')));
+        assert!(config.keys.is(Action::Fire, KeyCode::Char('x')));
+        assert!(!config.keys.is(Action::Fire, KeyCode::Char(' ')));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_config() {
+        assert!("theme = \"neon\"".parse::<Config>().is_err());
+        assert!("[keys]\nfire = [\"hyper\"]".parse::<Config>().is_err());
+        assert!("[keys]\nteleport = [\"t\"]".parse::<Config>().is_err());
+        assert!("colour = \"red\"".parse::<Config>().is_err());
+
+        let err = "[keys]\nfire = [\"q\"]".parse::<Config>().unwrap_err();
+        assert!(format!("{:#}", err).contains("Q is bound to both fire and quit"));
+        let err = "[keys]\npreset = \"vim\"\nquit = [\"h\"]".parse::<Config>();
+        assert!(format!("{:#}", err.unwrap_err()).contains("H is bound to both left and quit"));
+    }
+
+    #[test]
+    fn key_from_str() -> Result<()> {
+        assert_eq!("x".parse::<Key>()?, Key(KeyCode::Char('x')));
+        assert_eq!("Space".parse::<Key>()?, Key(KeyCode::Char(' ')));
+        assert_eq!("pagedown".parse::<Key>()?, Key(KeyCode::PageDown));
+        assert_eq!("f5".parse::<Key>()?, Key(KeyCode::F(5)));
+        assert!("f13".parse::<Key>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn describe_keys() {
+        let keys = KeyBindings::default();
+        let movement = [Action::Up, Action::Left, Action::Down, Action::Right];
+
+        assert_eq!(keys.describe(&movement), "WASD/↑←↓→");
+        assert_eq!(keys.describe(&[Action::Quit]), "Q/Esc");
+        assert_eq!(
+            keys.describe(&[Action::ScrollUp, Action::ScrollDown]),
+            "PgUp PgDn"
+        );
+    }
+}
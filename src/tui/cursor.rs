@@ -18,6 +18,15 @@ impl Cursor {
         }
     }
 
+    /// Moves to the given point, if it is within bounds.
+    pub fn move_to(&mut self, point: Point) -> bool {
+        let within = point.0 < self.cols && point.1 < self.rows;
+        if within {
+            self.point = point;
+        }
+        within
+    }
+
     pub fn up(&mut self) {
         self.point = Point(self.point.0, self.point.1.saturating_sub(1));
     }
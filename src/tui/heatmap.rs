@@ -0,0 +1,55 @@
+use crate::core::{Point, Shot};
+
+/// When a cell was fired at, compared to the other shots of the game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Heat {
+    /// The move of the game the cell was fired at, counting from 1.
+    pub turn: usize,
+
+    /// From 0 for the first move to 1 for the last.
+    pub heat: f64,
+}
+
+/// The cells of the target's grid that were fired at, colored by when.
+pub fn heatmap(shots: &[Shot], target: usize) -> Vec<(Point, Heat)> {
+    let last = shots.len().saturating_sub(1).max(1) as f64;
+    shots
+        .iter()
+        .enumerate()
+        .filter(|(_, shot)| shot.target == target)
+        .map(|(i, shot)| {
+            let heat = Heat {
+                turn: i + 1,
+                heat: i as f64 / last,
+            };
+            (shot.point, heat)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::Fire;
+
+    use super::*;
+
+    #[test]
+    fn heat_follows_shot_order() {
+        let shots = (0..5)
+            .map(|i| Shot {
+                shooter: i % 2,
+                target: 1 - i % 2,
+                point: Point(i, 0),
+                fire: Fire::Miss,
+            })
+            .collect::<Vec<_>>();
+
+        let heatmap = heatmap(&shots, 1);
+
+        let turns = heatmap.iter().map(|(_, h)| h.turn).collect::<Vec<_>>();
+        assert_eq!(turns, [1, 3, 5]);
+        assert_eq!(heatmap[0].1.heat, 0.0);
+        assert_eq!(heatmap[1], (Point(2, 0), Heat { turn: 3, heat: 0.5 }));
+        assert_eq!(heatmap[2].1.heat, 1.0);
+    }
+}
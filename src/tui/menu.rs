@@ -0,0 +1,643 @@
+use std::path::PathBuf;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use eyre::{eyre, Result};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    prelude::Stylize,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Paragraph, Widget,
+    },
+    Frame,
+};
+use strum::IntoEnumIterator;
+
+use crate::{
+    core::{Active, Difficulty, Game, Point, Resolution, Rules, Ship, ShotClock, Variant},
+    profile::Profiles,
+};
+
+use super::{
+    app::{centered_rect, App},
+    config::{Action, Config},
+    terminal,
+    widgets::ProfileWidget,
+};
+
+/// Width of the menu, not counting the frame around the screen.
+const MENU_WIDTH: u16 = 46;
+
+/// Room for the labels in front of the values.
+const LABEL_WIDTH: usize = 13;
+
+/// How long player names may be.
+const MAX_NAME_LENGTH: usize = 16;
+
+/// Width of the stats screen.
+const STATS_WIDTH: u16 = 55;
+
+/// The choices made in the menu, which are kept to play again.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Setup {
+    /// Two bots play against each other while the user watches.
+    pub spectate: bool,
+
+    /// Number of turns the spectator view lags behind the game.
+    pub delay: usize,
+
+    pub grid_size: usize,
+    pub fleet: Vec<Ship>,
+    pub variant: Variant,
+    pub resolution: Resolution,
+
+    /// How well the bots play.
+    pub difficulty: Difficulty,
+
+    pub names: [String; 2],
+    pub shot_clock: Option<ShotClock>,
+}
+
+impl Setup {
+    pub fn new(
+        spectate: bool,
+        delay: usize,
+        difficulty: Difficulty,
+        shot_clock: Option<ShotClock>,
+    ) -> Self {
+        let grid_size = 10;
+        Self {
+            spectate,
+            delay,
+            grid_size,
+            fleet: Ship::for_grid(grid_size),
+            variant: Variant::default(),
+            resolution: Resolution::default(),
+            difficulty,
+            names: Self::default_names(spectate),
+            shot_clock,
+        }
+    }
+
+    fn default_names(spectate: bool) -> [String; 2] {
+        match spectate {
+            true => ["Alice".into(), "Bob".into()],
+            false => ["Player".into(), "Bot".into()],
+        }
+    }
+
+    pub fn rules(&self) -> Rules {
+        Rules {
+            fleet: self.fleet.clone(),
+            variant: self.variant,
+            shot_clock: self.shot_clock,
+            resolution: self.resolution,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.rules().validate(self.grid_size)?;
+        if self.names.iter().any(|name| name.trim().is_empty()) {
+            return Err(eyre!("Both players need a name."));
+        }
+        if self.names[0].trim() == self.names[1].trim() {
+            return Err(eyre!("The players need different names."));
+        }
+        Ok(())
+    }
+
+    /// Sets up a game with the ships placed randomly.
+    pub fn game(&self) -> Result<Game<Active>> {
+        self.validate()?;
+        let mut game = Game::with_rules(self.grid_size, self.rules())?;
+        for name in &self.names {
+            game.add_player(name.trim()).place_ships_randomly()?;
+        }
+        game.start()
+    }
+
+    pub fn app(&self, config: Config) -> Result<App> {
+        let game = self.game()?;
+        let app = match self.spectate {
+            true => App::spectate(game, self.delay),
+            false => App::new(game),
+        };
+        Ok(app.config(config).difficulty(self.difficulty))
+    }
+
+    /// Toggles whether the ship is in the fleet, keeping the fleet in the usual order.
+    fn toggle_ship(&mut self, ship: Ship) {
+        let in_fleet = !self.fleet.contains(&ship);
+        self.fleet = Ship::iter()
+            .filter(|s| match *s == ship {
+                true => in_fleet,
+                false => self.fleet.contains(s),
+            })
+            .collect();
+    }
+}
+
+/// A line of the menu.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Field {
+    Mode,
+    GridSize,
+    Variant,
+    Resolution,
+    Difficulty,
+    Ship(Ship),
+    Name(usize),
+    Start,
+    Stats,
+}
+
+impl Field {
+    fn all() -> Vec<Field> {
+        let mut fields = vec![
+            Self::Mode,
+            Self::GridSize,
+            Self::Variant,
+            Self::Resolution,
+            Self::Difficulty,
+        ];
+        fields.extend(Ship::iter().map(Self::Ship));
+        fields.extend([Self::Name(0), Self::Name(1), Self::Start, Self::Stats]);
+        fields
+    }
+}
+
+/// The screen to set up a game before playing.
+#[derive(Debug)]
+pub struct Menu {
+    pub setup: Setup,
+    config: Config,
+    profiles: Option<PathBuf>,
+    fields: Vec<Field>,
+    selected: usize,
+    editing: bool,
+
+    /// The stats being shown instead of the menu.
+    stats: Option<Profiles>,
+
+    error: Option<String>,
+    app: Option<App>,
+    exit: bool,
+}
+
+impl Menu {
+    pub fn new(setup: Setup, config: Config) -> Self {
+        let fields = Field::all();
+        Self {
+            setup,
+            config,
+            profiles: None,
+            selected: fields.iter().position(|f| *f == Field::Start).unwrap(),
+            fields,
+            editing: false,
+            stats: None,
+            error: None,
+            app: None,
+            exit: false,
+        }
+    }
+
+    /// Where the player's stats are kept.
+    pub fn profiles(mut self, path: Option<PathBuf>) -> Self {
+        self.profiles = path;
+        self
+    }
+
+    /// Shows the menu until a game is started, which is returned, or the user quits.
+    pub fn run(&mut self, terminal: &mut terminal::Type) -> Result<Option<App>> {
+        loop {
+            terminal.draw(|frame| self.render_frame(frame))?;
+            match event::read()? {
+                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                    self.handle_key_event(key_event)
+                }
+                _ => {}
+            }
+            if let Some(app) = self.app.take() {
+                return Ok(Some(app));
+            }
+            if self.exit {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn render_frame(&self, frame: &mut Frame) {
+        frame.render_widget(self, frame.size());
+    }
+
+    fn field(&self) -> Field {
+        self.fields[self.selected]
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if self.editing {
+            return self.handle_name_key_event(key_event);
+        }
+        let actions = [
+            Action::Up,
+            Action::Down,
+            Action::Left,
+            Action::Right,
+            Action::Fire,
+            Action::Confirm,
+            Action::Quit,
+        ];
+        let Some(action) = actions
+            .into_iter()
+            .find(|action| self.config.keys.is(*action, key_event.code))
+        else {
+            return;
+        };
+        if self.stats.is_some() {
+            if let Action::Confirm | Action::Quit = action {
+                self.stats = None;
+            }
+            return;
+        }
+        match action {
+            Action::Up => self.selected = self.selected.saturating_sub(1),
+            Action::Down => self.selected = (self.selected + 1).min(self.fields.len() - 1),
+            Action::Left => self.change(-1),
+            Action::Right | Action::Fire => self.change(1),
+            Action::Confirm => match self.field() {
+                Field::Name(_) => self.editing = true,
+                Field::Stats => self.show_stats(),
+                _ => self.start(),
+            },
+            Action::Quit => self.exit = true,
+            _ => {}
+        }
+    }
+
+    /// Handles typing in the selected name.
+    fn handle_name_key_event(&mut self, key_event: KeyEvent) {
+        let Field::Name(i) = self.field() else {
+            return;
+        };
+        let name = &mut self.setup.names[i];
+        match key_event.code {
+            KeyCode::Enter | KeyCode::Esc => self.editing = false,
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() && name.chars().count() < MAX_NAME_LENGTH => {
+                name.push(c);
+            }
+            _ => {}
+        }
+        self.error = None;
+    }
+
+    /// Steps the selected value forward or backward.
+    fn change(&mut self, step: isize) {
+        let field = self.field();
+        let setup = &mut self.setup;
+        match field {
+            Field::Mode => {
+                // Names the user has not chosen follow the mode
+                if setup.names == Setup::default_names(setup.spectate) {
+                    setup.names = Setup::default_names(!setup.spectate);
+                }
+                setup.spectate = !setup.spectate;
+            }
+            Field::GridSize => {
+                setup.grid_size = setup
+                    .grid_size
+                    .saturating_add_signed(step)
+                    .clamp(2, Point::MAX_GRID_SIZE);
+            }
+            Field::Variant => setup.variant = cycle(setup.variant, step),
+            Field::Resolution => setup.resolution = cycle(setup.resolution, step),
+            Field::Difficulty => setup.difficulty = cycle(setup.difficulty, step),
+            Field::Ship(ship) => setup.toggle_ship(ship),
+            Field::Name(_) | Field::Start | Field::Stats => {}
+        }
+        self.error = None;
+    }
+
+    fn start(&mut self) {
+        match self.setup.app(self.config.clone()) {
+            Ok(app) => self.app = Some(app.profiles(self.profiles.clone())),
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    fn show_stats(&mut self) {
+        let Some(path) = &self.profiles else {
+            self.error = Some("Found no data directory for stats.".into());
+            return;
+        };
+        match Profiles::load(path) {
+            Ok(profiles) => self.stats = Some(profiles),
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    fn label(&self, field: Field) -> &'static str {
+        match (field, self.setup.spectate) {
+            (Field::Mode, _) => "Mode",
+            (Field::GridSize, _) => "Grid",
+            (Field::Variant, _) => "Rules",
+            (Field::Resolution, _) => "Turns",
+            (Field::Difficulty, false) => "Opponent",
+            (Field::Difficulty, true) => "Bots",
+            (Field::Ship(ship), _) if Some(ship) == Ship::iter().next() => "Fleet",
+            (Field::Ship(_), _) => "",
+            (Field::Name(0), false) => "Your name",
+            (Field::Name(_), false) => "Bot's name",
+            (Field::Name(0), true) => "First bot",
+            (Field::Name(_), true) => "Second bot",
+            (Field::Start | Field::Stats, _) => "",
+        }
+    }
+
+    fn value(&self, field: Field) -> String {
+        let setup = &self.setup;
+        match field {
+            Field::Mode => match setup.spectate {
+                true => "Watch two bots".into(),
+                false => "Play against a bot".into(),
+            },
+            Field::GridSize => format!("{}x{}", setup.grid_size, setup.grid_size),
+            Field::Variant => setup.variant.to_string(),
+            Field::Resolution => setup.resolution.to_string(),
+            Field::Difficulty => setup.difficulty.to_string(),
+            Field::Ship(ship) => {
+                let checkbox = match setup.fleet.contains(&ship) {
+                    true => "[x]",
+                    false => "[ ]",
+                };
+                format!("{} {} ({})", checkbox, ship, ship.length())
+            }
+            Field::Name(i) if self.editing && field == self.field() => {
+                format!("{}_", setup.names[i])
+            }
+            Field::Name(i) => setup.names[i].clone(),
+            Field::Start => "Start game".into(),
+            Field::Stats => "Stats".into(),
+        }
+    }
+
+    fn line(&self, field: Field) -> Line<'static> {
+        let is_selected = field == self.field();
+        let value = match field {
+            Field::Mode
+            | Field::GridSize
+            | Field::Variant
+            | Field::Resolution
+            | Field::Difficulty
+                if is_selected =>
+            {
+                format!("< {} >", self.value(field))
+            }
+            _ => self.value(field),
+        };
+        let label = format!("{:<width$}", self.label(field), width = LABEL_WIDTH);
+        match (is_selected, field) {
+            (true, Field::Start | Field::Stats) => Line::from(value.bold().reversed()).centered(),
+            (false, Field::Start | Field::Stats) => Line::from(value.bold()).centered(),
+            (true, _) => Line::from(vec![Span::from("> "), label.bold(), value.bold().yellow()]),
+            (false, _) => Line::from(vec![Span::from("  "), label.into(), value.into()]),
+        }
+    }
+}
+
+/// Steps to the next or previous value of the enum, wrapping around.
+fn cycle<T: IntoEnumIterator + PartialEq>(value: T, step: isize) -> T {
+    let values = T::iter().collect::<Vec<_>>();
+    let i = values.iter().position(|v| *v == value).unwrap_or(0);
+    let i = (i as isize + step).rem_euclid(values.len() as isize) as usize;
+    values.into_iter().nth(i).unwrap()
+}
+
+impl Widget for &Menu {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = Title::from(" Slagskip ".bold().yellow());
+        let keys = &self.config.keys;
+        let hints: &[(&str, &[Action])] = match (self.editing, self.field()) {
+            _ if self.stats.is_some() => &[("Back", &[Action::Confirm, Action::Quit])],
+            (true, _) => &[],
+            (false, Field::Name(_)) => &[
+                ("Choose", &[Action::Up, Action::Down]),
+                ("Edit name", &[Action::Confirm]),
+                ("Quit", &[Action::Quit]),
+            ],
+            (false, Field::Stats) => &[
+                ("Choose", &[Action::Up, Action::Down]),
+                ("Show stats", &[Action::Confirm]),
+                ("Quit", &[Action::Quit]),
+            ],
+            (false, _) => &[
+                ("Choose", &[Action::Up, Action::Down]),
+                ("Change", &[Action::Left, Action::Right]),
+                ("Start", &[Action::Confirm]),
+                ("Quit", &[Action::Quit]),
+            ],
+        };
+        let mut instructions = vec![];
+        for (label, actions) in hints {
+            instructions.push(format!(" {} ", label).into());
+            instructions.push(format!("<{}>", keys.describe(actions)).blue().bold());
+        }
+        if self.editing {
+            instructions.push(" Done <Enter>".into());
+        }
+        instructions.push(" ".into());
+        Block::default()
+            .title(title.alignment(Alignment::Center))
+            .title(
+                Title::from(Line::from(instructions))
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .border_set(border::THICK)
+            .render(area, buf);
+
+        if let Some(profiles) = &self.stats {
+            let name = self.setup.names[0].trim();
+            let profile = profiles.get(name);
+            let rows = profile.map_or(0, |p| p.records().count() + 1);
+            let stats_area = centered_rect(STATS_WIDTH, rows.max(2) as u16 + 4, area);
+            return ProfileWidget::new(name, profile).render(stats_area, buf);
+        }
+
+        let mut lines = vec![];
+        for field in &self.fields {
+            if *field == Field::Start {
+                lines.push(Line::default());
+            }
+            lines.push(self.line(*field));
+        }
+        let error = self
+            .error
+            .clone()
+            .or_else(|| self.setup.validate().err().map(|err| err.to_string()));
+        lines.push(Line::default());
+        lines.push(Line::from(error.unwrap_or_default().red()).centered());
+
+        let menu_area = centered_rect(MENU_WIDTH, lines.len() as u16 + 2, area);
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(Title::from(" New game ").alignment(Alignment::Center))
+                    .borders(Borders::ALL),
+            )
+            .render(menu_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn menu() -> Menu {
+        Menu::new(
+            Setup::new(false, 0, Difficulty::default(), None),
+            Config::default(),
+        )
+    }
+
+    fn select(menu: &mut Menu, field: Field) {
+        menu.selected = menu.fields.iter().position(|f| *f == field).unwrap();
+    }
+
+    #[test]
+    fn change_grid_size() {
+        let mut menu = menu();
+        select(&mut menu, Field::GridSize);
+
+        menu.handle_key_event(KeyCode::Right.into());
+        assert_eq!(menu.setup.grid_size, 11);
+
+        for _ in 0..30 {
+            menu.handle_key_event(KeyCode::Right.into());
+        }
+        assert_eq!(menu.setup.grid_size, Point::MAX_GRID_SIZE);
+    }
+
+    #[test]
+    fn toggle_ships() {
+        let mut menu = menu();
+        select(&mut menu, Field::Ship(Ship::Battleship));
+
+        menu.handle_key_event(KeyCode::Char(' ').into());
+        assert!(!menu.setup.fleet.contains(&Ship::Battleship));
+
+        menu.handle_key_event(KeyCode::Char(' ').into());
+        assert_eq!(menu.setup.fleet, Ship::for_grid(10));
+    }
+
+    #[test]
+    fn invalid_setup_does_not_start() {
+        let mut menu = menu();
+        menu.setup.fleet.clear();
+        select(&mut menu, Field::Start);
+
+        menu.handle_key_event(KeyCode::Enter.into());
+
+        assert!(menu.app.is_none());
+        assert_eq!(
+            menu.error.as_deref(),
+            Some("The fleet must have at least one ship.")
+        );
+    }
+
+    #[test]
+    fn start_game() {
+        let mut menu = menu();
+
+        menu.handle_key_event(KeyCode::Enter.into());
+
+        assert!(menu.app.is_some());
+        assert!(menu.error.is_none());
+    }
+
+    #[test]
+    fn show_stats() {
+        let mut menu = menu().profiles(Some(std::env::temp_dir().join("slagskip-missing.toml")));
+        select(&mut menu, Field::Stats);
+
+        menu.handle_key_event(KeyCode::Enter.into());
+        assert_eq!(menu.stats, Some(Profiles::default()));
+
+        // Going back from the stats does not quit
+        menu.handle_key_event(KeyCode::Char('q').into());
+        assert!(menu.stats.is_none());
+        assert!(!menu.exit);
+    }
+
+    #[test]
+    fn edit_name() {
+        let mut menu = menu();
+        select(&mut menu, Field::Name(0));
+
+        menu.handle_key_event(KeyCode::Enter.into());
+        for _ in 0.."Player".len() {
+            menu.handle_key_event(KeyCode::Backspace.into());
+        }
+        // Keys that are bound to actions are typed while editing
+        for c in "Sam Q".chars() {
+            menu.handle_key_event(KeyCode::Char(c).into());
+        }
+        menu.handle_key_event(KeyCode::Enter.into());
+
+        assert_eq!(menu.setup.names[0], "Sam Q");
+        assert!(!menu.editing);
+        assert!(!menu.exit);
+    }
+
+    #[test]
+    fn mode_switches_default_names() {
+        let mut menu = menu();
+        select(&mut menu, Field::Mode);
+
+        menu.handle_key_event(KeyCode::Right.into());
+        assert!(menu.setup.spectate);
+        assert_eq!(menu.setup.names, ["Alice", "Bob"]);
+
+        menu.setup.names[0] = "Sam".into();
+        menu.handle_key_event(KeyCode::Right.into());
+        assert!(!menu.setup.spectate);
+        assert_eq!(menu.setup.names, ["Sam", "Bob"]);
+    }
+
+    #[test]
+    fn setup_game() -> Result<()> {
+        let setup = Setup {
+            grid_size: 6,
+            fleet: vec![Ship::Cruiser, Ship::Destroyer],
+            variant: Variant::NoTouch,
+            resolution: Resolution::Simultaneous,
+            names: ["Sam".into(), "Robot".into()],
+            ..Setup::new(false, 0, Difficulty::default(), None)
+        };
+
+        let game = setup.game()?;
+
+        assert_eq!(game.grid_size, 6);
+        assert_eq!(game.rules.variant, Variant::NoTouch);
+        assert_eq!(game.rules.resolution, Resolution::Simultaneous);
+        assert_eq!(game.players[0].name, "Sam");
+        assert_eq!(game.players[1].ships(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn players_need_different_names() {
+        let setup = Setup {
+            names: ["Sam".into(), "Sam ".into()],
+            ..Setup::new(false, 0, Difficulty::default(), None)
+        };
+
+        assert!(setup.validate().is_err());
+    }
+}
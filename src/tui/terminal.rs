@@ -1,5 +1,6 @@
 use std::{io, panic};
 
+use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
 };
@@ -10,11 +11,13 @@ pub type Type = Terminal<CrosstermBackend<io::Stdout>>;
 
 pub fn init() -> io::Result<Type> {
     io::stdout().execute(EnterAlternateScreen)?;
+    io::stdout().execute(EnableMouseCapture)?;
     enable_raw_mode()?;
     Terminal::new(CrosstermBackend::new(io::stdout()))
 }
 
 pub fn restore() -> io::Result<()> {
+    io::stdout().execute(DisableMouseCapture)?;
     io::stdout().execute(LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
@@ -23,6 +26,7 @@ pub fn restore() -> io::Result<()> {
 pub fn install_panic_hook() {
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
+        io::stdout().execute(DisableMouseCapture).unwrap();
         io::stdout().execute(LeaveAlternateScreen).unwrap();
         disable_raw_mode().unwrap();
         original_hook(panic_info);
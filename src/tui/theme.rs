@@ -0,0 +1,280 @@
+use ratatui::{
+    prelude::Stylize,
+    style::{Color, Style},
+    symbols::border,
+    text::Span,
+};
+use serde::Deserialize;
+
+use crate::core::{Direction, Fire, Hull, Point};
+
+use super::heatmap::Heat;
+
+/// How the cells of a grid look.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    PartialEq,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+    strum_macros::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum Theme {
+    #[default]
+    Default,
+
+    /// Colors that stay apart with color vision deficiencies, with glyphs for hits and misses.
+    Colorblind,
+
+    /// No colors, only ASCII glyphs.
+    Monochrome,
+}
+
+/// What a cell shows, as far as the viewer knows.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CellState {
+    Water,
+    Ship,
+
+    /// A hit on a ship that is still afloat.
+    Wreck,
+
+    Sunk,
+    Miss,
+}
+
+/// Where in a ship a cell is, which decides how the hull is drawn.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HullPart {
+    Bow,
+    Middle,
+    Stern,
+}
+
+impl HullPart {
+    /// The part of the ship at the given point, if the ship covers it.
+    pub fn of(hull: &Hull, point: Point) -> Option<Self> {
+        let i = hull.points().position(|p| p == point)?;
+        match i {
+            0 => Some(Self::Bow),
+            i if i + 1 == hull.length() => Some(Self::Stern),
+            _ => Some(Self::Middle),
+        }
+    }
+}
+
+// The Okabe-Ito palette
+const BLUE: Color = Color::Rgb(0, 114, 178);
+const YELLOW: Color = Color::Rgb(240, 228, 66);
+const VERMILLION: Color = Color::Rgb(213, 94, 0);
+const PURPLE: Color = Color::Rgb(204, 121, 167);
+
+/// Shades of the ship color, one per class, so that ships next to each other stand apart.
+const GREENS: [Color; 5] = [
+    Color::Rgb(0, 135, 0),
+    Color::Rgb(0, 175, 95),
+    Color::Rgb(95, 175, 0),
+    Color::Rgb(0, 135, 95),
+    Color::Rgb(95, 135, 0),
+];
+const YELLOWS: [Color; 5] = [
+    YELLOW,
+    Color::Rgb(230, 190, 50),
+    Color::Rgb(250, 245, 140),
+    Color::Rgb(215, 200, 60),
+    Color::Rgb(245, 210, 100),
+];
+
+/// Stops of the viridis palette, which is readable with color vision deficiencies.
+const VIRIDIS: [(u8, u8, u8); 3] = [(68, 1, 84), (33, 145, 140), (253, 231, 37)];
+
+/// Border lines that only use ASCII characters.
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// Blends between the stops of the viridis palette, from 0 for purple to 1 for yellow.
+fn viridis(heat: f64) -> Color {
+    let scaled = heat.clamp(0.0, 1.0) * (VIRIDIS.len() - 1) as f64;
+    let i = (scaled as usize).min(VIRIDIS.len() - 2);
+    let t = scaled - i as f64;
+    let (from, to) = (VIRIDIS[i], VIRIDIS[i + 1]);
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    Color::Rgb(mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+fn glyph(compact: bool, wide: &'static str, narrow: &'static str) -> &'static str {
+    match compact {
+        true => narrow,
+        false => wide,
+    }
+}
+
+impl Theme {
+    /// A cell two characters wide, or a single character if compact.
+    pub fn cell(&self, state: CellState, compact: bool) -> Span<'static> {
+        let glyph = |wide, narrow| glyph(compact, wide, narrow);
+        match (self, state) {
+            (Self::Default, CellState::Water) => glyph("  ", " ").on_blue(),
+            (Self::Default, CellState::Ship) => glyph("  ", " ").on_green(),
+            (Self::Default, CellState::Wreck) => glyph("  ", " ").on_red(),
+            (Self::Default, CellState::Sunk) => glyph("  ", " ").on_dark_gray(),
+            (Self::Default, CellState::Miss) => glyph("  ", " ").on_black(),
+            (Self::Colorblind, CellState::Water) => glyph("  ", " ").bg(BLUE),
+            (Self::Colorblind, CellState::Ship) => glyph("  ", " ").bg(YELLOW),
+            (Self::Colorblind, CellState::Wreck) => glyph("XX", "X").black().bold().bg(VERMILLION),
+            (Self::Colorblind, CellState::Sunk) => glyph("##", "#").fg(VERMILLION).on_dark_gray(),
+            (Self::Colorblind, CellState::Miss) => glyph("()", "o").white().on_black(),
+            (Self::Monochrome, CellState::Water) => glyph("  ", ".").into(),
+            (Self::Monochrome, CellState::Ship) => glyph("[]", "#").into(),
+            (Self::Monochrome, CellState::Wreck) => glyph("XX", "X").bold(),
+            (Self::Monochrome, CellState::Sunk) => glyph("##", "#").reversed(),
+            (Self::Monochrome, CellState::Miss) => glyph("()", "o").into(),
+        }
+    }
+
+    /// A cell of a ship, drawn as the part of the hull it is, shaded by ship class.
+    pub fn hull(
+        &self,
+        hull: &Hull,
+        part: HullPart,
+        state: CellState,
+        compact: bool,
+    ) -> Span<'static> {
+        let text = match (hull.direction, part) {
+            (Direction::Horizontal, HullPart::Bow) => glyph(compact, "<=", "<"),
+            (Direction::Horizontal, HullPart::Middle) => glyph(compact, "==", "="),
+            (Direction::Horizontal, HullPart::Stern) => glyph(compact, "=>", ">"),
+            (Direction::Vertical, HullPart::Bow) => glyph(compact, "/\\", "^"),
+            (Direction::Vertical, HullPart::Middle) => glyph(compact, "||", "|"),
+            (Direction::Vertical, HullPart::Stern) => glyph(compact, "\\/", "v"),
+        };
+        let shade = hull.ship as usize % GREENS.len();
+        match (self, state) {
+            (Self::Default, CellState::Ship) => text.black().bg(GREENS[shade]),
+            (Self::Default, CellState::Wreck) => text.black().on_red(),
+            (Self::Default, CellState::Sunk) => text.red().on_dark_gray(),
+            (Self::Colorblind, CellState::Ship) => text.black().bg(YELLOWS[shade]),
+            (Self::Colorblind, CellState::Wreck) => text.black().bold().bg(VERMILLION),
+            (Self::Colorblind, CellState::Sunk) => text.fg(VERMILLION).on_dark_gray(),
+            (Self::Monochrome, CellState::Ship) => text.into(),
+            (Self::Monochrome, CellState::Sunk) => text.reversed(),
+            (_, state) => self.cell(state, compact),
+        }
+    }
+
+    /// The cell that was just fired at, flashing.
+    pub fn flash(&self, fire: Fire, compact: bool) -> Span<'static> {
+        let glyph = |wide, narrow| glyph(compact, wide, narrow);
+        match (self, fire) {
+            (Self::Default, Fire::Miss) => glyph("~~", "~").white().on_blue(),
+            (Self::Default, Fire::Hit) => glyph("**", "*").black().on_yellow(),
+            (Self::Default, Fire::Sunk(_)) => glyph("##", "#").white().on_magenta(),
+            (Self::Colorblind, Fire::Miss) => glyph("~~", "~").white().bg(BLUE),
+            (Self::Colorblind, Fire::Hit) => glyph("**", "*").black().on_white(),
+            (Self::Colorblind, Fire::Sunk(_)) => glyph("##", "#").black().bg(PURPLE),
+            (Self::Monochrome, Fire::Miss) => glyph("~~", "~").reversed(),
+            (Self::Monochrome, Fire::Hit) => glyph("**", "*").reversed(),
+            (Self::Monochrome, Fire::Sunk(_)) => glyph("##", "#").reversed().bold(),
+        }
+    }
+
+    /// The cell that was most recently fired at.
+    pub fn last_shot(&self, state: CellState, compact: bool) -> Span<'static> {
+        let cell = self.cell(state, compact);
+        let marker = glyph(compact, "<>", "@");
+        match self {
+            Self::Default => Span::styled(marker, cell.style.yellow().bold()),
+            Self::Colorblind => Span::styled(marker, cell.style.white().bold()),
+            Self::Monochrome => cell.reversed(),
+        }
+    }
+
+    /// A cell in the heatmap of shots, numbered by turn if there is room, with hits in bold.
+    pub fn heat(&self, heat: Heat, state: CellState, compact: bool) -> Span<'static> {
+        let is_hit = matches!(state, CellState::Wreck | CellState::Sunk);
+        let text = match (compact, heat.turn, is_hit) {
+            (false, 1..=99, _) => format!("{:>2}", heat.turn),
+            (_, _, true) => glyph(compact, "XX", "X").to_string(),
+            (_, _, false) => glyph(compact, "()", "o").to_string(),
+        };
+        let span = match self {
+            Self::Monochrome => Span::raw(text),
+            _ if heat.heat < 0.5 => text.white().bg(viridis(heat.heat)),
+            _ => text.black().bg(viridis(heat.heat)),
+        };
+        match is_hit {
+            true => span.bold(),
+            false => span,
+        }
+    }
+
+    pub fn cell_border(&self, is_active: bool, is_last_shot: bool) -> Style {
+        match (self, is_active, is_last_shot) {
+            (Self::Monochrome, true, _) => Style::new().bold(),
+            (Self::Monochrome, false, _) => Style::new(),
+            (_, true, _) => Style::new().white(),
+            (Self::Default, false, true) => Style::new().yellow(),
+            (_, false, _) => Style::new().dark_gray(),
+        }
+    }
+
+    pub fn cell_border_set(&self) -> border::Set {
+        match self {
+            Self::Monochrome => ASCII_BORDER,
+            _ => border::PLAIN,
+        }
+    }
+
+    pub fn log(&self, fire: Fire) -> Style {
+        match (self, fire) {
+            (_, Fire::Miss) => Style::new(),
+            (Self::Default, Fire::Hit) => Style::new().red(),
+            (Self::Colorblind, Fire::Hit) => Style::new().fg(VERMILLION),
+            (Self::Monochrome, Fire::Hit) => Style::new(),
+            (Self::Default, Fire::Sunk(_)) => Style::new().red().bold(),
+            (Self::Colorblind, Fire::Sunk(_)) => Style::new().fg(VERMILLION).bold(),
+            (Self::Monochrome, Fire::Sunk(_)) => Style::new().bold(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::Ship;
+
+    use super::*;
+
+    #[test]
+    fn hull_parts() {
+        let hull = Hull::new(Ship::Cruiser, Point(1, 1), Direction::Horizontal);
+
+        let parts = (0..5)
+            .map(|x| HullPart::of(&hull, Point(x, 1)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            parts,
+            [
+                None,
+                Some(HullPart::Bow),
+                Some(HullPart::Middle),
+                Some(HullPart::Stern),
+                None
+            ]
+        );
+    }
+}
@@ -1,7 +1,15 @@
 mod cell;
 mod grid;
+mod help;
+mod log;
 mod player;
+mod profile;
+mod stats;
 
-pub use cell::CellWidget;
+pub use cell::{CellSize, CellWidget};
 pub use grid::GridWidget;
+pub use help::HelpWidget;
+pub use log::LogWidget;
 pub use player::PlayerWidget;
+pub use profile::ProfileWidget;
+pub use stats::StatsWidget;
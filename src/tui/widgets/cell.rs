@@ -2,63 +2,150 @@ use ratatui::{
     buffer::Buffer,
     layout::Rect,
     prelude::Stylize,
-    style::Style,
+    text::Span,
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
-use crate::core::{Cell, Point};
+use crate::core::{Cell, Hull, Point};
+use crate::tui::animation::Effect;
+use crate::tui::heatmap::Heat;
+use crate::tui::theme::{CellState, HullPart, Theme};
 
-pub struct CellWidget<'a> {
-    cell: &'a Cell,
+/// How much room each cell takes on screen.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CellSize {
+    /// Two characters wide, with borders to the right and below.
+    #[default]
+    Normal,
+
+    /// A single character, without borders.
+    Compact,
+}
+
+impl CellSize {
+    pub fn width(&self) -> usize {
+        match self {
+            Self::Normal => 2 + 1, // Text and border right
+            Self::Compact => 1,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            Self::Normal => 1 + 1, // Line of text and border bottom
+            Self::Compact => 1,
+        }
+    }
+
+    pub fn is_compact(&self) -> bool {
+        *self == Self::Compact
+    }
+}
+
+pub struct CellWidget {
+    cell: Cell,
     with_ships: bool,
     is_active: bool,
+    is_last_shot: bool,
+    effect: Option<Effect>,
+    heat: Option<Heat>,
+    hull: Option<(Hull, HullPart)>,
+    theme: Theme,
+    size: CellSize,
 }
 
-impl<'a> CellWidget<'a> {
-    pub fn new(point: Point, cell: &'a Cell, with_ships: bool, cursor: Option<Point>) -> Self {
+impl CellWidget {
+    pub fn new(point: Point, cell: Cell, with_ships: bool, cursor: Option<Point>) -> Self {
         Self {
             cell,
             with_ships,
             is_active: cursor.is_some_and(|c| c == point),
+            is_last_shot: false,
+            effect: None,
+            heat: None,
+            hull: None,
+            theme: Theme::default(),
+            size: CellSize::default(),
         }
     }
 
-    pub fn content_height() -> usize {
-        1 // Line of text
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn size(mut self, size: CellSize) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn last_shot(mut self, is_last_shot: bool) -> Self {
+        self.is_last_shot = is_last_shot;
+        self
     }
 
-    pub fn content_width() -> usize {
-        2 // Line of text
+    pub fn effect(mut self, effect: Option<Effect>) -> Self {
+        self.effect = effect;
+        self
     }
 
-    pub fn box_height() -> usize {
-        CellWidget::content_height() + 1 // Border bottom
+    pub fn heat(mut self, heat: Option<Heat>) -> Self {
+        self.heat = heat;
+        self
     }
 
-    pub fn box_width() -> usize {
-        CellWidget::content_width() + 1 // Border right
+    /// The ship in the cell, and which part of it the cell is.
+    pub fn hull(mut self, hull: Option<(Hull, HullPart)>) -> Self {
+        self.hull = hull;
+        self
     }
 }
 
-impl Widget for CellWidget<'_> {
+impl Widget for CellWidget {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let text = match (self.cell.has_ship(), self.cell.is_hit()) {
-            (Some(_ship), false) if self.with_ships => "  ".on_green(),
-            (Some(_ship), true) => "  ".on_red(),
-            (_, false) => "  ".on_blue(),
-            (_, true) => "  ".on_black(),
+        let is_sunk = self.hull.is_some_and(|(hull, _)| hull.is_sunk());
+        let state = match (self.cell.has_ship(), self.cell.is_hit()) {
+            (Some(_ship), false) if self.with_ships => CellState::Ship,
+            (Some(_ship), true) if is_sunk => CellState::Sunk,
+            (Some(_ship), true) => CellState::Wreck,
+            (_, false) => CellState::Water,
+            (_, true) => CellState::Miss,
         };
-        let border_style = if self.is_active {
-            Style::new().white()
-        } else {
-            Style::new().dark_gray()
+        // The shape of a hidden ship is only given away once it is sunk
+        let hull = self.hull.filter(|_| self.with_ships || is_sunk);
+        let compact = self.size.is_compact();
+        let text = match (self.effect, self.heat, self.is_last_shot) {
+            // Flash every other frame until the animation is done
+            (Some(Effect { fire, frame }), _, _) if frame % 2 == 0 => {
+                self.theme.flash(fire, compact)
+            }
+            (_, Some(heat), _) => self.theme.heat(heat, state, compact),
+            (None, None, true) => self.theme.last_shot(state, compact),
+            _ => match (hull, state) {
+                (Some((hull, part)), CellState::Ship | CellState::Wreck | CellState::Sunk) => {
+                    self.theme.hull(&hull, part, state, compact)
+                }
+                _ => self.theme.cell(state, compact),
+            },
         };
-        Paragraph::new(text)
-            .block(
-                Block::default()
-                    .borders(Borders::RIGHT | Borders::BOTTOM)
-                    .border_style(border_style),
-            )
-            .render(area, buf);
+        match self.size {
+            CellSize::Normal => Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .borders(Borders::RIGHT | Borders::BOTTOM)
+                        .border_set(self.theme.cell_border_set())
+                        .border_style(self.theme.cell_border(self.is_active, self.is_last_shot)),
+                )
+                .render(area, buf),
+            // Without borders, the cursor can only be shown in the cell itself
+            CellSize::Compact if self.is_active => {
+                let text = match text.content.trim() {
+                    "" => Span::styled("+", text.style),
+                    _ => text,
+                };
+                Paragraph::new(text.reversed().bold()).render(area, buf)
+            }
+            CellSize::Compact => Paragraph::new(text).render(area, buf),
+        }
     }
 }
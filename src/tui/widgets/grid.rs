@@ -1,17 +1,33 @@
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Direction, Layout, Rect},
-    widgets::Widget,
+    layout::Rect,
+    prelude::Stylize,
+    text::Span,
+    widgets::{Paragraph, Widget},
 };
 
 use crate::core::{Grid, Point};
+use crate::tui::animation::Effect;
+use crate::tui::heatmap::Heat;
+use crate::tui::theme::{HullPart, Theme};
 
-use super::CellWidget;
+use super::{CellSize, CellWidget};
+
+/// Room for the row numbers to the left of the grid.
+const ROW_LABEL_WIDTH: usize = 3;
+
+/// Room for the column letters above the grid.
+const COLUMN_LABEL_HEIGHT: usize = 1;
 
 pub struct GridWidget<'a> {
     grid: &'a Grid,
     with_ships: bool,
     cursor: Option<Point>,
+    last_shot: Option<Point>,
+    effects: Vec<(Point, Effect)>,
+    heatmap: Vec<(Point, Heat)>,
+    theme: Theme,
+    cell_size: CellSize,
 }
 
 impl<'a> GridWidget<'a> {
@@ -20,54 +36,181 @@ impl<'a> GridWidget<'a> {
             grid,
             with_ships,
             cursor,
+            last_shot: None,
+            effects: vec![],
+            heatmap: vec![],
+            theme: Theme::default(),
+            cell_size: CellSize::default(),
         }
     }
 
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn last_shot(mut self, last_shot: Option<Point>) -> Self {
+        self.last_shot = last_shot;
+        self
+    }
+
+    pub fn effects(mut self, effects: Vec<(Point, Effect)>) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    pub fn heatmap(mut self, heatmap: Vec<(Point, Heat)>) -> Self {
+        self.heatmap = heatmap;
+        self
+    }
+
+    pub fn cell_size(mut self, cell_size: CellSize) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
     pub fn content_width(&self) -> usize {
-        CellWidget::box_width() * self.grid.size
+        self.cell_size.width() * self.grid.size
     }
 
     pub fn box_width(&self) -> usize {
-        self.content_width()
+        ROW_LABEL_WIDTH + self.content_width()
     }
 
     pub fn content_height(&self) -> usize {
-        CellWidget::box_height() * self.grid.size
+        self.cell_size.height() * self.grid.size
     }
 
     pub fn box_height(&self) -> usize {
-        self.content_height()
+        COLUMN_LABEL_HEIGHT + self.content_height()
+    }
+
+    /// The part of the grid that fits in `area`, keeping the cursor or last shot in view.
+    fn viewport(&self, area: Rect) -> Viewport {
+        let focus = self
+            .cursor
+            .or(self.last_shot)
+            .unwrap_or(Point(self.grid.size / 2, self.grid.size / 2));
+        let visible = |available: u16, label: usize, cell: usize, focus: usize| {
+            let count = (available as usize).saturating_sub(label) / cell;
+            let count = count.min(self.grid.size);
+            let offset = focus.saturating_sub(count / 2).min(self.grid.size - count);
+            (offset, count)
+        };
+        let (x, cols) = visible(area.width, ROW_LABEL_WIDTH, self.cell_size.width(), focus.0);
+        let (y, rows) = visible(
+            area.height,
+            COLUMN_LABEL_HEIGHT,
+            self.cell_size.height(),
+            focus.1,
+        );
+        Viewport {
+            offset: Point(x, y),
+            cols,
+            rows,
+        }
     }
+
+    fn cell_rect(&self, area: Rect, col: usize, row: usize) -> Rect {
+        Rect {
+            x: area.x + (ROW_LABEL_WIDTH + col * self.cell_size.width()) as u16,
+            y: area.y + (COLUMN_LABEL_HEIGHT + row * self.cell_size.height()) as u16,
+            width: self.cell_size.width() as u16,
+            height: self.cell_size.height() as u16,
+        }
+    }
+
+    /// Finds the cell at the given screen position, if the grid was rendered in `area`.
+    pub fn point_at(&self, area: Rect, column: u16, row: u16) -> Option<Point> {
+        let viewport = self.viewport(area);
+        let col =
+            column.checked_sub(area.x + ROW_LABEL_WIDTH as u16)? as usize / self.cell_size.width();
+        let row = row.checked_sub(area.y + COLUMN_LABEL_HEIGHT as u16)? as usize
+            / self.cell_size.height();
+        if col >= viewport.cols || row >= viewport.rows {
+            return None;
+        }
+        Some(Point(viewport.offset.0 + col, viewport.offset.1 + row))
+    }
+
+    fn label(&self, text: String, is_active: bool) -> Span<'static> {
+        match is_active {
+            true => text.white().bold(),
+            false => text.dark_gray(),
+        }
+    }
+}
+
+/// The columns and rows of the grid that are shown.
+struct Viewport {
+    offset: Point,
+    cols: usize,
+    rows: usize,
 }
 
 impl Widget for GridWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let row_constraints =
-            std::iter::repeat(Constraint::Length(CellWidget::box_height() as u16))
-                .take(self.grid.size)
-                .collect::<Vec<_>>();
-        let col_constraints = std::iter::repeat(Constraint::Length(CellWidget::box_width() as u16))
-            .take(self.grid.size)
-            .collect::<Vec<_>>();
-
-        let row_rects = Layout::default()
-            .direction(Direction::Vertical)
-            .horizontal_margin(0)
-            .vertical_margin(0)
-            .constraints(row_constraints)
-            .split(area);
-        for (y, row_rect) in row_rects.iter().enumerate() {
-            let col_rects = Layout::default()
-                .direction(Direction::Horizontal)
-                .horizontal_margin(0)
-                .vertical_margin(0)
-                .constraints(col_constraints.clone())
-                .split(*row_rect);
-            for (x, cell_rect) in col_rects.iter().enumerate() {
-                let point = Point(x, y);
-                let cell = self.grid.at(point).unwrap();
-                let widget = CellWidget::new(point, cell, self.with_ships, self.cursor);
-                widget.render(*cell_rect, buf);
+        let viewport = self.viewport(area);
+        let hulls = self.grid.hulls();
+
+        for col in 0..viewport.cols {
+            let x = viewport.offset.0 + col;
+            let letter = (b'A' + x as u8) as char;
+            let is_active = self.cursor.is_some_and(|c| c.0 == x);
+            let label = match self.cell_size {
+                CellSize::Normal => format!("{:^2}", letter),
+                CellSize::Compact => letter.to_string(),
+            };
+            let rect = self.cell_rect(area, col, 0);
+            Paragraph::new(self.label(label, is_active)).render(
+                Rect {
+                    y: area.y,
+                    height: COLUMN_LABEL_HEIGHT as u16,
+                    ..rect
+                },
+                buf,
+            );
+        }
+
+        for row in 0..viewport.rows {
+            let y = viewport.offset.1 + row;
+            let is_active = self.cursor.is_some_and(|c| c.1 == y);
+            let rect = self.cell_rect(area, 0, row);
+            Paragraph::new(self.label(format!("{:>2}", y + 1), is_active)).render(
+                Rect {
+                    x: area.x,
+                    width: ROW_LABEL_WIDTH as u16,
+                    ..rect
+                },
+                buf,
+            );
+
+            for col in 0..viewport.cols {
+                let point = Point(viewport.offset.0 + col, y);
+                let Some(cell) = self.grid.at(point) else {
+                    continue;
+                };
+                let effect = self
+                    .effects
+                    .iter()
+                    .find(|(p, _)| *p == point)
+                    .map(|(_, effect)| *effect);
+                let heat = self
+                    .heatmap
+                    .iter()
+                    .find(|(p, _)| *p == point)
+                    .map(|(_, heat)| *heat);
+                let hull = hulls
+                    .iter()
+                    .find_map(|hull| Some((*hull, HullPart::of(hull, point)?)));
+                let widget = CellWidget::new(point, cell, self.with_ships, self.cursor)
+                    .hull(hull)
+                    .last_shot(self.last_shot == Some(point))
+                    .theme(self.theme)
+                    .size(self.cell_size)
+                    .effect(effect)
+                    .heat(heat);
+                widget.render(self.cell_rect(area, col, row), buf);
             }
         }
     }
@@ -0,0 +1,158 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Margin, Rect},
+    prelude::Stylize,
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, Clear, Paragraph, Widget},
+};
+use strum::IntoEnumIterator;
+
+use crate::core::{Direction, Fire, Hull, Point, Resolution, Rules, Ship, Variant};
+use crate::tui::config::{Action, KeyBindings};
+use crate::tui::heatmap::Heat;
+use crate::tui::theme::{CellState, HullPart, Theme};
+
+/// Room between the columns.
+const GAP: u16 = 3;
+
+/// The key bindings, the rules of the game, and what the cells look like.
+pub struct HelpWidget<'a> {
+    keys: &'a KeyBindings,
+    rules: &'a Rules,
+    theme: Theme,
+}
+
+impl<'a> HelpWidget<'a> {
+    pub fn new(keys: &'a KeyBindings, rules: &'a Rules) -> Self {
+        Self {
+            keys,
+            rules,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// The width and height needed to show all of the help.
+    pub fn size(&self) -> (u16, u16) {
+        let (keys, rules) = (self.keys_column(), self.rules_column());
+        let width = |lines: &[Line]| lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let height = keys.len().max(rules.len()) as u16;
+        (width(&keys) + GAP + width(&rules) + 4, height + 2)
+    }
+
+    fn keys_column(&self) -> Vec<Line<'static>> {
+        let keys = Action::iter()
+            .map(|action| (action, self.keys.describe(&[action])))
+            .collect::<Vec<_>>();
+        let key_width = keys
+            .iter()
+            .map(|(_, k)| k.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec![Line::from("Keys".bold())];
+        for (action, key) in keys {
+            lines.push(Line::from(vec![
+                format!("{:<width$}  ", key, width = key_width)
+                    .blue()
+                    .bold(),
+                action.description().into(),
+            ]));
+        }
+        lines
+    }
+
+    fn rules_column(&self) -> Vec<Line<'static>> {
+        let theme = self.theme;
+        let variant = match self.rules.variant {
+            Variant::Classic => "ships may touch",
+            Variant::NoTouch => "ships may not touch",
+        };
+        let mut lines = vec![
+            Line::from("Rules".bold()),
+            Line::from(format!("{} ({})", self.rules.variant, variant)),
+        ];
+        if self.rules.resolution == Resolution::Simultaneous {
+            lines.push(Line::from("simultaneous (a sunk player fires back)"));
+        }
+        if let Some(shot_clock) = self.rules.shot_clock {
+            lines.push(Line::from(shot_clock.to_string()));
+        }
+
+        lines.extend([Line::default(), Line::from("Fleet".bold())]);
+        let name_width = Ship::iter().map(|s| s.to_string().len()).max().unwrap_or(0);
+        for ship in &self.rules.fleet {
+            let mut spans = vec![Span::from(format!(
+                "{:<width$} {} ",
+                ship.to_string(),
+                ship.length(),
+                width = name_width
+            ))];
+            spans.extend((0..ship.length()).map(|_| theme.cell(CellState::Ship, true)));
+            lines.push(Line::from(spans));
+        }
+
+        lines.extend([Line::default(), Line::from("Cells".bold())]);
+        let hull = |state| {
+            let hull = Hull::new(Ship::Destroyer, Point(0, 0), Direction::Horizontal);
+            vec![
+                theme.hull(&hull, HullPart::Bow, state, false),
+                theme.hull(&hull, HullPart::Stern, state, false),
+            ]
+        };
+        let legend = [
+            (vec![theme.cell(CellState::Water, false)], "Water"),
+            (hull(CellState::Ship), "Ship"),
+            (hull(CellState::Wreck), "Hit"),
+            (hull(CellState::Sunk), "Sunk"),
+            (vec![theme.cell(CellState::Miss, false)], "Miss"),
+            (vec![theme.last_shot(CellState::Miss, false)], "Last shot"),
+            (vec![theme.flash(Fire::Hit, false)], "Just hit"),
+            (
+                vec![theme.flash(Fire::Sunk(Ship::Destroyer), false)],
+                "Just sunk",
+            ),
+        ];
+        let heat = (0..2)
+            .map(|i| {
+                let heat = Heat {
+                    turn: i + 1,
+                    heat: i as f64,
+                };
+                theme.heat(heat, CellState::Miss, false)
+            })
+            .collect();
+        for (mut cells, label) in legend.into_iter().chain([(heat, "Shot order in review")]) {
+            // Two cells wide, so that the labels line up
+            let width = cells.iter().map(Span::width).sum::<usize>();
+            cells.push(format!("{:width$}{}", "", label, width = 5 - width.min(4)).into());
+            lines.push(Line::from(cells));
+        }
+        lines
+    }
+}
+
+impl Widget for HelpWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(Title::from(" Help ".bold()).alignment(Alignment::Center))
+            .borders(Borders::ALL);
+        let inner = block.inner(area).inner(&Margin::new(1, 0));
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let keys = self.keys_column();
+        let key_width = keys.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let [keys_area, _, rules_area] = Layout::horizontal([
+            Constraint::Length(key_width),
+            Constraint::Length(GAP),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+        Paragraph::new(keys).render(keys_area, buf);
+        Paragraph::new(self.rules_column()).render(rules_area, buf);
+    }
+}
@@ -0,0 +1,77 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    prelude::Stylize,
+    text::Line,
+    widgets::{block::Title, Block, Borders, Paragraph, Widget},
+};
+
+use crate::core::{Active, Fire, Game, Shot};
+use crate::tui::theme::Theme;
+
+pub struct LogWidget<'a> {
+    game: &'a Game<Active>,
+    shots: &'a [Shot],
+    scroll: usize,
+    theme: Theme,
+}
+
+impl<'a> LogWidget<'a> {
+    pub fn new(game: &'a Game<Active>, shots: &'a [Shot]) -> Self {
+        Self {
+            game,
+            shots,
+            scroll: 0,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Scrolls back the given number of shots from the most recent one.
+    pub fn scroll(mut self, scroll: usize) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    fn describe(&self, shot: &Shot) -> Line<'a> {
+        let shooter = &self.game.players[shot.shooter].name;
+        let target = &self.game.players[shot.target].name;
+        let text = match shot.fire {
+            Fire::Miss => format!("{} fired at {} {} and missed.", shooter, target, shot.point),
+            Fire::Hit => format!("{} fired at {} {} and hit!", shooter, target, shot.point),
+            Fire::Sunk(ship) => format!(
+                "{} fired at {} {} and sunk a {}!",
+                shooter, target, shot.point, ship
+            ),
+        };
+        Line::styled(text, self.theme.log(shot.fire))
+    }
+}
+
+impl Widget for LogWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let scroll = self.scroll.min(self.shots.len());
+        let title = match scroll {
+            0 => " Log ".to_string(),
+            n => format!(" Log ({} newer) ", n),
+        };
+        let block = Block::default()
+            .title(Title::from(title.bold()).alignment(Alignment::Center))
+            .borders(Borders::ALL);
+
+        // Only the shots that fit are shown, by default the most recent ones.
+        let height = block.inner(area).height as usize;
+        let end = self.shots.len() - scroll;
+        let start = end.saturating_sub(height);
+        let lines = self.shots[start..end]
+            .iter()
+            .map(|shot| self.describe(shot))
+            .collect::<Vec<_>>();
+
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
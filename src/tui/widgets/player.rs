@@ -1,7 +1,12 @@
-use crate::core::{Active, Player, Point};
+use std::{rc::Rc, time::Duration};
+
+use crate::core::{Difficulty, Grid, Hull, Point};
+use crate::tui::animation::Effect;
 use crate::tui::app::centered_rect;
-use ratatui::style::{Color, Style};
-use ratatui::widgets::{Block, Gauge, Widget};
+use crate::tui::heatmap::Heat;
+use crate::tui::theme::{CellState, Theme};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Widget};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -9,58 +14,237 @@ use ratatui::{
     widgets::{block::Title, Borders},
 };
 
-use super::GridWidget;
+use super::{CellSize, GridWidget};
+
+const HORIZONTAL_MARGIN: u16 = 2;
 
 pub struct PlayerWidget<'a> {
-    player: &'a Player<Active>,
+    name: &'a str,
+    grid: &'a Grid,
     with_ships: bool,
     cursor: Option<Point>,
+    difficulty: Option<Difficulty>,
+    time_left: Option<Duration>,
+    prompt: Option<&'a str>,
+    last_shot: Option<Point>,
+    effects: Vec<(Point, Effect)>,
+    heatmap: Vec<(Point, Heat)>,
+    theme: Theme,
+    cell_size: CellSize,
 }
 
 impl<'a> PlayerWidget<'a> {
-    pub fn new(player: &'a Player<Active>, with_ships: bool, cursor: Option<Point>) -> Self {
+    pub fn new(name: &'a str, grid: &'a Grid, with_ships: bool, cursor: Option<Point>) -> Self {
         Self {
-            player,
+            name,
+            grid,
             with_ships,
             cursor,
+            difficulty: None,
+            time_left: None,
+            prompt: None,
+            last_shot: None,
+            effects: vec![],
+            heatmap: vec![],
+            theme: Theme::default(),
+            cell_size: CellSize::default(),
         }
     }
-}
 
-impl Widget for PlayerWidget<'_> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let frame_block = Block::default()
-            .title(Title::from(self.player.name.clone().bold()).alignment(Alignment::Center))
-            .borders(Borders::ALL);
-        frame_block.render(area, buf);
+    pub fn cell_size(mut self, cell_size: CellSize) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    fn grid_widget(&self) -> GridWidget<'a> {
+        GridWidget::new(self.grid, self.with_ships, self.cursor)
+            .last_shot(self.last_shot)
+            .theme(self.theme)
+            .cell_size(self.cell_size)
+    }
+
+    /// The width needed to show the whole grid.
+    pub fn box_width(&self) -> usize {
+        self.grid_widget().box_width() + 2 + 2 * HORIZONTAL_MARGIN as usize
+    }
+
+    /// The height needed to show the whole grid, with the lines below it.
+    pub fn box_height(&self) -> usize {
+        let grid_widget = self.grid_widget();
+        let fleet = self.fleet_lines(grid_widget.box_width()).len();
+        grid_widget.box_height() + 2 + 1 + fleet
+    }
+
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Shows how well the player plays, for bots.
+    pub fn difficulty(mut self, difficulty: Option<Difficulty>) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Shows a countdown of the time left to fire.
+    pub fn time_left(mut self, time_left: Option<Duration>) -> Self {
+        self.time_left = time_left;
+        self
+    }
 
-        let layout = Layout::default()
+    /// Shows the coordinates being typed in to fire at.
+    pub fn prompt(mut self, prompt: Option<&'a str>) -> Self {
+        self.prompt = prompt;
+        self
+    }
+
+    /// Highlights the most recent shot at the grid.
+    pub fn last_shot(mut self, last_shot: Option<Point>) -> Self {
+        self.last_shot = last_shot;
+        self
+    }
+
+    /// Animates the given cells of the grid.
+    pub fn effects(mut self, effects: Vec<(Point, Effect)>) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    /// Colors the cells that were fired at by when.
+    pub fn heatmap(mut self, heatmap: Vec<(Point, Heat)>) -> Self {
+        self.heatmap = heatmap;
+        self
+    }
+
+    /// Finds the grid cell at the given screen position, if any.
+    pub fn point_at(&self, area: Rect, column: u16, row: u16) -> Option<Point> {
+        let grid_widget = self.grid_widget();
+        let grid_area = Self::grid_area(&grid_widget, self.layout(area)[0]);
+        grid_widget.point_at(grid_area, column, row)
+    }
+
+    fn layout(&self, area: Rect) -> Rc<[Rect]> {
+        let width = area.width.saturating_sub(2 + 2 * HORIZONTAL_MARGIN);
+        let fleet = self.fleet_lines(width as usize).len();
+        Layout::default()
             .direction(Direction::Vertical)
-            .horizontal_margin(2)
-            .vertical_margin(2)
+            .horizontal_margin(HORIZONTAL_MARGIN)
+            .vertical_margin(1)
             .constraints([
-                Constraint::Percentage(100),
-                Constraint::Length(1),
+                Constraint::Fill(1),
                 Constraint::Length(1),
+                Constraint::Length(fleet as u16),
             ])
-            .split(area);
+            .split(area)
+    }
+
+    /// The ships of the fleet, wrapped to the given width, with sunk ships
+    /// crossed out and, if the ships are shown, the damage to the others.
+    fn fleet_lines(&self, width: usize) -> Vec<Line<'static>> {
+        let compact = self.cell_size.is_compact();
+        let mut hulls = self.grid.hulls().to_vec();
+        hulls.sort_by_key(|hull| hull.ship);
+        let entries = hulls.iter().map(|hull| self.fleet_entry(hull, compact));
+
+        let separator = if compact { 1 } else { 2 };
+        let mut lines: Vec<Vec<Span>> = vec![];
+        let mut line_width = 0;
+        for entry in entries {
+            let entry_width = entry.iter().map(Span::width).sum::<usize>();
+            match lines.last_mut() {
+                Some(line) if line_width + separator + entry_width <= width => {
+                    line.push(" ".repeat(separator).into());
+                    line.extend(entry);
+                    line_width += separator + entry_width;
+                }
+                _ => {
+                    lines.push(entry);
+                    line_width = entry_width;
+                }
+            }
+        }
+        lines
+            .into_iter()
+            .map(|line| Line::from(line).centered())
+            .collect()
+    }
 
-        let grid_widget = GridWidget::new(&self.player.grid, self.with_ships, self.cursor);
-        let grid_area = centered_rect(
+    /// A ship of the fleet, named unless compact, with a cell per length.
+    fn fleet_entry(&self, hull: &Hull, compact: bool) -> Vec<Span<'static>> {
+        let mut spans = vec![];
+        if !compact {
+            let name = format!("{} ", hull.ship);
+            spans.push(match hull.is_sunk() {
+                true => name.crossed_out().dark_gray(),
+                false => name.into(),
+            });
+        }
+        spans.extend(hull.points().map(|point| {
+            let is_hit = self.grid.at(point).is_some_and(|cell| cell.is_hit());
+            let state = match (hull.is_sunk(), self.with_ships && is_hit) {
+                (true, _) => CellState::Sunk,
+                (false, true) => CellState::Wreck,
+                (false, false) => CellState::Ship,
+            };
+            self.theme.cell(state, true)
+        }));
+        spans
+    }
+
+    fn grid_area(grid_widget: &GridWidget, area: Rect) -> Rect {
+        centered_rect(
             grid_widget.box_width() as u16,
             grid_widget.box_height() as u16,
-            layout[0],
-        );
+            area,
+        )
+    }
+}
+
+impl Widget for PlayerWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut title = vec![self.name.bold()];
+        if let Some(difficulty) = self.difficulty {
+            title.push(format!(" ({})", difficulty).into());
+        }
+        let frame_block = Block::default()
+            .title(Title::from(Line::from(title)).alignment(Alignment::Center))
+            .borders(Borders::ALL);
+        frame_block.render(area, buf);
+
+        let layout = self.layout(area);
+        let fleet = self.fleet_lines(layout[2].width as usize);
+
+        let grid_widget = self
+            .grid_widget()
+            .effects(self.effects)
+            .heatmap(self.heatmap);
+        let grid_area = Self::grid_area(&grid_widget, layout[0]);
         grid_widget.render(grid_area, buf);
 
-        Gauge::default()
-            .gauge_style(Style::default().fg(Color::Green).bg(Color::Red))
-            .ratio(self.player.num_ships_alive() as f64 / self.player.num_ships_total() as f64)
-            .label(format!(
-                "{}/{}",
-                self.player.num_ships_alive(),
-                self.player.num_ships_total()
-            ))
-            .render(layout[2], buf);
+        if let Some(time_left) = self.time_left {
+            // Round up, so that the countdown ends at the moment time runs out.
+            let seconds = time_left.as_millis().div_ceil(1000);
+            let countdown = format!("{}s left to fire", seconds);
+            let countdown = if seconds <= 5 {
+                countdown.red().bold()
+            } else {
+                countdown.into()
+            };
+            Paragraph::new(countdown)
+                .alignment(Alignment::Center)
+                .render(layout[1], buf);
+        }
+
+        if let Some(prompt) = self.prompt {
+            Paragraph::new(Line::from(vec![
+                "Fire at: ".into(),
+                prompt.yellow().bold(),
+                "_".yellow().slow_blink(),
+            ]))
+            .alignment(Alignment::Center)
+            .render(layout[1], buf);
+        }
+
+        Paragraph::new(fleet).render(layout[2], buf);
     }
 }
@@ -0,0 +1,90 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    text::Line,
+    widgets::{block::Title, Block, Borders, Paragraph, Row, Table, Widget},
+};
+
+use crate::profile::{Profile, Record};
+
+/// A player's record against the bots, per difficulty.
+pub struct ProfileWidget<'a> {
+    name: &'a str,
+    profile: Option<&'a Profile>,
+}
+
+impl<'a> ProfileWidget<'a> {
+    pub fn new(name: &'a str, profile: Option<&'a Profile>) -> Self {
+        Self { name, profile }
+    }
+}
+
+impl Widget for ProfileWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(
+                Title::from(format!(" Stats for {} ", self.name).bold())
+                    .alignment(Alignment::Center),
+            )
+            .borders(Borders::ALL);
+        let Some(profile) = self.profile else {
+            return Paragraph::new(vec![
+                Line::default(),
+                Line::from("No games played yet.").centered(),
+            ])
+            .block(block)
+            .render(area, buf);
+        };
+
+        let row = |label: String, record: &Record| {
+            Row::new([
+                label,
+                record.games().to_string(),
+                record.wins.to_string(),
+                record.losses.to_string(),
+                record.draws.to_string(),
+                format!("{:.0}%", record.win_rate() * 100.0),
+                match record.average_shots_to_win() {
+                    Some(shots) => format!("{:.1}", shots),
+                    None => "-".into(),
+                },
+                match record.best_game {
+                    Some(shots) => shots.to_string(),
+                    None => "-".into(),
+                },
+            ])
+        };
+        let total = profile.total();
+        let mut rows = profile
+            .records()
+            .map(|(difficulty, record)| row(difficulty.to_string(), record))
+            .collect::<Vec<_>>();
+        rows.push(row("Total".into(), &total).bold());
+        let header = Row::new([
+            "Opponent",
+            "Games",
+            "Wins",
+            "Losses",
+            "Draws",
+            "Won",
+            "Avg shots",
+            "Best",
+        ])
+        .bold();
+        let widths = [
+            Constraint::Length(9),
+            Constraint::Length(5),
+            Constraint::Length(4),
+            Constraint::Length(6),
+            Constraint::Length(5),
+            Constraint::Length(4),
+            Constraint::Length(9),
+            Constraint::Length(4),
+        ];
+        Table::new(rows, widths)
+            .header(header.bottom_margin(1))
+            .block(block)
+            .render(area, buf);
+    }
+}
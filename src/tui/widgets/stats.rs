@@ -0,0 +1,76 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    widgets::{
+        block::{Position, Title},
+        Block, Borders, Row, Table, Widget,
+    },
+};
+
+use crate::core::{Active, Game, Shot, Stats};
+
+/// Compares how the players fired, up to the move being reviewed.
+pub struct StatsWidget<'a> {
+    game: &'a Game<Active>,
+    shots: &'a [Shot],
+    moves: usize,
+}
+
+impl<'a> StatsWidget<'a> {
+    pub fn new(game: &'a Game<Active>, shots: &'a [Shot]) -> Self {
+        Self {
+            game,
+            shots,
+            moves: shots.len(),
+        }
+    }
+
+    /// The number of moves in the whole game, if only some of the shots are given.
+    pub fn moves(mut self, moves: usize) -> Self {
+        self.moves = moves;
+        self
+    }
+}
+
+impl Widget for StatsWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let stats = (0..self.game.players.len())
+            .map(|i| Stats::new(self.shots, i))
+            .collect::<Vec<_>>();
+        let row = |label: &'static str, value: &dyn Fn(&Stats) -> String| {
+            let mut cells = vec![label.to_string()];
+            cells.extend(stats.iter().map(value));
+            Row::new(cells)
+        };
+        let rows = [
+            row("Shots", &|s| s.shots.to_string()),
+            row("Hits", &|s| s.hits.to_string()),
+            row("Accuracy", &|s| format!("{:.0}%", s.accuracy() * 100.0)),
+            row("Longest streak", &|s| s.longest_streak.to_string()),
+            row("First sink", &|s| match s.shots_to_first_sink {
+                Some(shots) => format!("{} shots", shots),
+                None => "-".into(),
+            }),
+        ];
+        let header = Row::new(
+            std::iter::once(String::new()).chain(self.game.players.iter().map(|p| p.name.clone())),
+        )
+        .bold();
+        let mut widths = vec![Constraint::Length(15)];
+        widths.extend(self.game.players.iter().map(|_| Constraint::Fill(1)));
+
+        let block = Block::default()
+            .title(Title::from(" Stats ".bold()).alignment(Alignment::Center))
+            .title(
+                Title::from(format!(" Move {}/{} ", self.shots.len(), self.moves))
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL);
+        Table::new(rows, widths)
+            .header(header.bottom_margin(1))
+            .block(block)
+            .render(area, buf);
+    }
+}
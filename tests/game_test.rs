@@ -4,60 +4,42 @@ use slagskip::core::{Direction, Game, GameResult, Point, Ship};
 #[test]
 fn one_ship_game() -> Result<()> {
     let mut game = Game::new(2);
-    game.add_player("Alice");
-    game.add_player("Bob");
+    game.add_player("Alice")
+        .place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal)?;
+    game.add_player("Bob")
+        .place_ship(Ship::Destroyer, Point(1, 0), Direction::Vertical)?;
 
-    {
-        let alice = &game.players[0];
-        let bob = &game.players[1];
+    let mut game = game.start()?;
 
-        // Place ships
-        alice.place_ship(Ship::Destroyer, Point(0, 0), Direction::Horizontal)?;
-        bob.place_ship(Ship::Destroyer, Point(1, 0), Direction::Vertical)?;
-    }
-
-    let game = game.start()?;
-
-    {
-        let alice = &game.players[0];
-        let bob = &game.players[1];
-
-        assert!(alice.is_alive());
-        assert!(bob.is_alive());
+    assert!(game.players[0].is_alive());
+    assert!(game.players[1].is_alive());
 
-        // Let everyone have 2 turns
-        for i in 0..2 {
-            for turn in game.round() {
-                for opponent in turn.opponents.iter() {
-                    opponent.fire_at(Point(0, i));
-                }
-            }
-        }
-
-        assert_eq!(alice.grid.to_string(), ["XO", "_."].join("\n"));
-        assert_eq!(bob.grid.to_string(), ["_O", "_O"].join("\n"));
-        assert!(alice.is_alive());
-        assert!(bob.is_alive());
-        assert!(game.result().is_none());
+    // Let everyone have 2 turns
+    for i in 0..2 {
+        game.fire(0, 1, Point(0, i));
+        game.fire(1, 0, Point(0, i));
+    }
 
-        // Let everyone have another turn
-        for turn in game.round() {
-            for opponent in turn.opponents.iter() {
-                opponent.fire_at(Point(1, 0));
-            }
+    assert_eq!(game.players[0].grid.to_string(), ["XO", "_."].join("\n"));
+    assert_eq!(game.players[1].grid.to_string(), ["_O", "_O"].join("\n"));
+    assert!(game.players[0].is_alive());
+    assert!(game.players[1].is_alive());
+    assert!(game.result().is_none());
+
+    // Let everyone have another turn
+    game.fire(0, 1, Point(1, 0));
+    game.fire(1, 0, Point(1, 0));
+
+    assert_eq!(game.players[0].grid.to_string(), ["XX", "_."].join("\n"));
+    assert_eq!(game.players[1].grid.to_string(), ["_X", "_O"].join("\n"));
+    assert!(!game.players[0].is_alive());
+    assert!(game.players[1].is_alive());
+    match game.result().unwrap() {
+        GameResult::Winner(winner) => {
+            assert_eq!(winner.name, "Bob");
         }
-
-        assert_eq!(alice.grid.to_string(), ["XX", "_."].join("\n"));
-        assert_eq!(bob.grid.to_string(), ["_X", "_O"].join("\n"));
-        assert!(!alice.is_alive());
-        assert!(bob.is_alive());
-        match game.result().unwrap() {
-            GameResult::Winner(winner) => {
-                assert_eq!(winner.name, "Bob");
-            }
-            GameResult::Draw => {
-                panic!("Game should end with a winner, but ended in a tie.")
-            }
+        GameResult::Draw(_) => {
+            panic!("Game should end with a winner, but ended in a tie.")
         }
     }
 